@@ -4,7 +4,8 @@ extern crate serde_yaml;
 use qamd::config::*;
 
 fn main() -> Result<(), serde_yaml::Error> {
-    let odd_chars = vec_of_strings(vec!["!", "#", "  ", "@", "ë", "ç", "ô", "ü"]);
+    let odd_chars = OddCharacters::new(vec_of_strings(vec!["!", "#", "  ", "@", "\\p{Cc}", "regex:[^[:ascii:]]"]))
+        .expect("Invalid odd characters");
     let dicts = vec_of_strings(vec![
         "/usr/share/dict/words",
         "C:\\path\\to\\dictonary\\file.txt",
@@ -13,6 +14,11 @@ fn main() -> Result<(), serde_yaml::Error> {
         "stopwords.txt",
     ]);
     let duplicate_values = vec_of_strings(vec!["Caseno"]);
+    let compound_keys = vec![vec_of_strings(vec!["HouseholdID", "PersonNo"])];
+    let value_ranges = ValueRanges(vec![
+        ("Age".to_string(), ValueRange { min: Some(0.0), max: Some(120.0), allowed: None }),
+        ("q*_likert".to_string(), ValueRange { min: None, max: None, allowed: Some(vec![1.0, 2.0, 3.0, 4.0, 5.0]) }),
+    ]);
     let regexps = vec_of_strings(vec![
         "^([\\w\\.\\-]+)@([\\w\\-]+)((\\.(\\w){2,4})+)$",
         "([Gg][Ii][Rr] 0[Aa]{2})|((([A-Za-z][0-9]{1,2})|(([A-Za-z][A-Ha-hJ-Yj-y][0-9]{1,2})|(([A-Za-z][0-9][A-Za-z])|([A-Za-z][A-Ha-hJ-Yj-y][0-9]?[A-Za-z]))))\\s?[0-9][A-Za-z]{2})",
@@ -22,6 +28,17 @@ fn main() -> Result<(), serde_yaml::Error> {
     let config = Config {
             metadata_only: None,
             progress: None,
+            locator_limit: Some(1000),
+            encoding: None,
+            csv: Some(CsvDialect {
+                delimiter: None,
+                quote: Some('"'),
+                escape: None,
+                has_headers: Some(true),
+                skip_rows: Some(0),
+                comment: None,
+                trim: Some(false),
+            }),
 
 
             basic_file_checks: BasicFileChecks {
@@ -49,19 +66,64 @@ fn main() -> Result<(), serde_yaml::Error> {
                 value_label_spellcheck: Some(setting(dicts.clone(), "Word file(s) used for spellchecking value labels.")),
 
                 value_defined_missing_no_label: Some(setting(true, "Values defined as missing must have a label (only applicable to SPSS data files)")),
+                values_without_labels: Some(setting(true, "Values of variables with value labels must have a label.")),
+                unused_value_labels: Some(setting(true, "Value labels must be used by a value and label sets attached to a variable.")),
+                measure_consistency: Some(setting(MeasureConsistency {
+                                                      scale_labelled_percentage: 50,
+                                                      nominal_max_distinct: 100,
+                                                      ordinal_strings: true,
+                                                  },
+                                                  "The measure of variables must be consistent with their labels and values (only applicable to SPSS data files).")),
+                missing_value_codes: Some(setting(vec![-9.0, -8.0, -1.0], "Variables with user-defined missing values must use the codes specified, or the codes most variables use when none are specified (only applicable to SPSS data files).")),
             },
             data_integrity: DataIntegrity {
                 duplicate_values: Some(setting(duplicate_values, "For each variable specified will check for duplicate values. Useful for checking all ID's are unique.")),
+                value_range: Some(setting(value_ranges, "Numeric values must fall within the range and allowed values given for variables matching each name or pattern.")),
+                duplicate_records: Some(setting(vec_of_strings(vec!["Caseno"]), "Whole records must not be repeated, ignoring the variables specified. Useful for finding rows pasted twice with a different ID.")),
+                outliers: Some(setting(Outliers {
+                                           method: OutlierMethod::Iqr,
+                                           threshold: 1.5,
+                                           variables: None,
+                                       },
+                                       "Numeric values should not fall outside the fences of the outlier method.")),
+                consistency_rules: Some(setting(vec![
+                                                    ConsistencyRule::new("Hours only when employed", "if Employed == 2 then missing(HoursWorked)")
+                                                        .expect("Invalid consistency rule"),
+                                                ],
+                                                "Every row must satisfy each named rule.")),
+                duplicate_compound_keys: Some(setting(compound_keys, "For each combination of variables specified will check for duplicate combined values. Useful for checking keys such as household and person number are unique.")),
 
                 string_value_odd_characters: Some(setting(odd_chars, "String values cannot contain certain 'odd' characters.")),
                 string_value_spellcheck: Some(setting(dicts, "Word file(s) used for spellchecking string values.")),
+                invisible_characters: Some(setting(true, "Variable names, labels, value labels and string values must not have leading or trailing whitespace, non-breaking spaces, zero-width or other invisible characters.")),
+                mojibake: Some(setting(true, "Labels and string values must not contain mojibake such as \"Ã©\" or \"â€™\", a sign the file was decoded with the wrong character encoding.")),
                 system_missing_value_threshold: Some(setting(25, "Percentage of missing variables that becomes unacceptable.")),
+                missing_value_types: Some(vec![MissingType::System, MissingType::Defined, MissingType::Tagged]),
+                record_missing_threshold: Some(setting(50, "Percentage of system or user-missing values in a record that becomes unacceptable.")),
+                constant_variables: Some(setting(vec![], "Variables must not hold the same value, or be entirely system or user-missing, for every case. Variables matching the names or patterns specified are exempt.")),
             },
             disclosure_risk: DisclosureRisk {
                 date_format: None,
 
                 regex_patterns: Some(setting(regexps, "Values matching a regex pattern fail. Can be used to find post codes and telephone numbers.")),
-                unique_values: Some(setting(1, "Detects outliers (if a variable contains unique values)")),
+                unique_values: Some(setting(1, "Detects variables containing unique or rare values (at or below the threshold number of observations)")),
+                k_anonymity: Some(setting(KAnonymity {
+                                              quasi_identifiers: vec![vec_of_strings(vec!["Age", "Sex", "Region"])],
+                                              k: 3,
+                                          },
+                                          "Combinations of quasi-identifiers must be shared by at least k records.")),
+                l_diversity: Some(setting(LDiversity {
+                                              quasi_identifiers: vec_of_strings(vec!["Age", "Sex", "Region"]),
+                                              sensitive_variables: vec_of_strings(vec!["HealthCondition"]),
+                                              l: 2,
+                                          },
+                                          "Records sharing quasi-identifiers must have at least l distinct values of each sensitive variable.")),
+                special_uniques: Some(setting(SpecialUniques {
+                                                  key_variables: vec_of_strings(vec!["Age", "Sex", "Region", "Occupation"]),
+                                                  max_subset_size: 3,
+                                                  top: 20,
+                                              },
+                                              "Scores records by the minimal sample uniques found across subsets of the key variables.")),
                 string_value_stopword: Some(setting(stopwords, "Find string values that contain words listed in a dictionary")),
             }
     };
@@ -104,6 +104,72 @@ fn parse_arguments() -> clap::ArgMatches<'static> {
                         .takes_value(true)
                         .possible_values(&["json", "html"]),
                 )
+                .arg(
+                    Arg::with_name("encoding")
+                        .long("encoding")
+                        .value_name("ENCODING")
+                        .help("Sets the character encoding of the input file, e.g. WINDOWS-1252. Overrides the encoding in the config file and the one declared by the input file. CSV files support UTF-8, ISO-8859-1 and WINDOWS-1252 only.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("delimiter")
+                        .long("delimiter")
+                        .value_name("CHAR")
+                        .help("Sets the delimiter of CSV files, use \\t or tab for tabs. Sniffed from the first lines if not set.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("quote")
+                        .long("quote")
+                        .value_name("CHAR")
+                        .help("Sets the quote character of CSV files.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("escape")
+                        .long("escape")
+                        .value_name("CHAR")
+                        .help("Sets the character escaping quotes in CSV files. Quotes are escaped by doubling them if not set.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("comment")
+                        .long("comment")
+                        .value_name("CHAR")
+                        .help("Sets the character starting comment lines in CSV files.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("skip-rows")
+                        .long("skip-rows")
+                        .value_name("ROWS")
+                        .help("Sets the number of lines to skip before the header or first record of CSV files.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("headers")
+                        .long("headers")
+                        .conflicts_with("no-headers")
+                        .help("If set, CSV files are read with a header row naming the variables."),
+                )
+                .arg(
+                    Arg::with_name("no-headers")
+                        .long("no-headers")
+                        .conflicts_with("headers")
+                        .help("If set, CSV files are read without a header row and variables are named V1..Vn."),
+                )
+                .arg(
+                    Arg::with_name("trim")
+                        .long("trim")
+                        .conflicts_with("no-trim")
+                        .help("If set, whitespace around CSV headers and fields is trimmed."),
+                )
+                .arg(
+                    Arg::with_name("no-trim")
+                        .long("no-trim")
+                        .conflicts_with("trim")
+                        .help("If set, whitespace around CSV headers and fields is kept."),
+                )
                 .arg(
                     Arg::with_name("metadata-only")
                         .short("m")
@@ -227,6 +293,15 @@ fn run(matches: &ArgMatches) {
             config.metadata_only = override_config(config.metadata_only, metadata_only);
             config.progress = override_config(config.progress, progress);
 
+            if let Some(encoding) = matches.value_of("encoding") {
+                config.encoding = Some(encoding.to_string());
+            }
+
+            if let Err(err) = override_csv_dialect(matches, config) {
+                eprintln!("Invalid CSV options: {}", err);
+                return;
+            }
+
             match read(&file_path, &config) {
                 Ok(report) => {
                     let serialised = match output_format {
@@ -280,6 +355,66 @@ fn override_config<T>(option: Option<T>, value: T) -> Option<T> {
     }
 }
 
+/// Override the CSV dialect of the config with the options given
+fn override_csv_dialect(matches: &ArgMatches, config: &mut Config) -> Result<(), String> {
+    let mut dialect = config.csv.clone().unwrap_or_default();
+
+    if let Some(delimiter) = matches.value_of("delimiter") {
+        dialect.delimiter = Some(parse_char(delimiter)?);
+    }
+
+    if let Some(quote) = matches.value_of("quote") {
+        dialect.quote = Some(parse_char(quote)?);
+    }
+
+    if let Some(escape) = matches.value_of("escape") {
+        dialect.escape = Some(parse_char(escape)?);
+    }
+
+    if let Some(comment) = matches.value_of("comment") {
+        dialect.comment = Some(parse_char(comment)?);
+    }
+
+    if let Some(rows) = matches.value_of("skip-rows") {
+        dialect.skip_rows = Some(
+            rows.parse::<i32>()
+                .map_err(|_| format!("skip rows must be a number, got {}", rows))?,
+        );
+    }
+
+    if matches.is_present("headers") {
+        dialect.has_headers = Some(true);
+    } else if matches.is_present("no-headers") {
+        dialect.has_headers = Some(false);
+    }
+
+    if matches.is_present("trim") {
+        dialect.trim = Some(true);
+    } else if matches.is_present("no-trim") {
+        dialect.trim = Some(false);
+    }
+
+    dialect.validate()?;
+    config.csv = Some(dialect);
+
+    Ok(())
+}
+
+/// Parse a single character option, accepting \t or tab for a tab
+fn parse_char(value: &str) -> Result<char, String> {
+    match value {
+        "\\t" | "tab" => Ok('\t'),
+        _ => {
+            let mut chars = value.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("expected a single character, got {:?}", value)),
+            }
+        }
+    }
+}
+
 fn write_to_file(path: &str, contents: &str) -> io::Result<()> {
     let f = File::create(path)?;
 
@@ -291,12 +426,21 @@ fn write_to_file(path: &str, contents: &str) -> io::Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_config, DEFAULT_CONFIG};
+    use super::{parse_char, parse_config, DEFAULT_CONFIG};
 
     #[test]
     fn test_config_parses() {
         assert!(parse_config(DEFAULT_CONFIG).is_ok(),
             "Configuration file did not parse correctly.");
     }
+
+    #[test]
+    fn test_parse_char() {
+        assert_eq!(parse_char(";"), Ok(';'));
+        assert_eq!(parse_char("\\t"), Ok('\t'));
+        assert_eq!(parse_char("tab"), Ok('\t'));
+        assert!(parse_char("").is_err());
+        assert!(parse_char(";;").is_err());
+    }
 }
 
@@ -2,7 +2,7 @@ use check::{CheckName, read_file};
 use readstat::context::Context;
 use report::{Category, Status, Locator};
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 /// Predicate to pass to the dictionary function.
 /// True value denotes the word passed the check
@@ -80,13 +80,7 @@ pub fn dictionary(context: &mut Context,
             } else {
                 if let Some(metadata_only) = config.metadata_only {
                     if !metadata_only {
-                        if let Some(ref mut locators) = status.locators {
-                            locators.insert(locator.clone());
-                        } else {
-                            let mut set = HashSet::new();
-                            set.insert(locator.clone());
-                            status.locators = Some(set);
-                        }
+                        status.add_locator(locator.clone(), config.max_locators());
                     }
                 }
 
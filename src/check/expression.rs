@@ -0,0 +1,503 @@
+//! A small expression language for consistency rules over the values of a
+//! row, e.g. `if employed == 2 then missing(hours_worked)`.
+//!
+//! ```text
+//! expression := "if" expression "then" expression | or
+//! or         := and (("or" | "||") and)*
+//! and        := not (("and" | "&&") not)*
+//! not        := ("not" | "!") not | atom
+//! atom       := "(" expression ")" | "missing(" name ")" | "sysmiss(" name ")"
+//!             | operand ("==" | "=" | "!=" | "<" | "<=" | ">" | ">=") operand
+//! operand    := number | "string" | 'string' | name
+//! ```
+//!
+//! Rules are evaluated with three-valued logic: a comparison against a
+//! missing value is unknown (`None`), and a rule is only broken when it
+//! evaluates to `Some(false)`.
+
+use model::missing::Missing;
+use model::value::Value;
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    If(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Missing(String),
+    SystemMissing(String),
+    Compare(Operator, Operand, Operand),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Number(f64),
+    Text(String),
+    Variable(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Number(f64),
+    Text(String),
+    Operator(Operator),
+    Not,
+    And,
+    Or,
+    LeftParen,
+    RightParen,
+}
+
+/// A literal, or the value of a variable, ready to be compared
+enum Resolved {
+    Number(f64),
+    Text(String),
+}
+
+impl Expression {
+    pub fn parse(input: &str) -> Result<Expression, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens: tokens,
+            position: 0,
+        };
+
+        let expression = parser.expression()?;
+
+        match parser.peek() {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    /// Names of the variables the expression refers to, in order of first use
+    pub fn variables(&self) -> Vec<String> {
+        fn push(name: &String, names: &mut Vec<String>) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        fn collect(expression: &Expression, names: &mut Vec<String>) {
+            match expression {
+                Expression::If(a, b) | Expression::Or(a, b) | Expression::And(a, b) => {
+                    collect(a, names);
+                    collect(b, names);
+                }
+                Expression::Not(a) => collect(a, names),
+                Expression::Missing(name) | Expression::SystemMissing(name) => push(name, names),
+                Expression::Compare(_, left, right) => {
+                    for operand in [left, right].iter() {
+                        if let Operand::Variable(name) = operand {
+                            push(name, names);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut names = vec![];
+        collect(self, &mut names);
+        names
+    }
+
+    /// Evaluate the expression, looking up the value of each variable by name.
+    /// `None` means the result is unknown because a value is missing or absent.
+    pub fn evaluate<'a, F>(&self, lookup: &F) -> Option<bool>
+    where
+        F: Fn(&str) -> Option<&'a Value>,
+    {
+        match self {
+            Expression::If(condition, consequence) => {
+                match condition.evaluate(lookup) {
+                    Some(false) => Some(true),
+                    Some(true) => consequence.evaluate(lookup),
+                    None => match consequence.evaluate(lookup) {
+                        Some(true) => Some(true),
+                        _ => None,
+                    },
+                }
+            }
+            Expression::Or(a, b) => match (a.evaluate(lookup), b.evaluate(lookup)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            Expression::And(a, b) => match (a.evaluate(lookup), b.evaluate(lookup)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Expression::Not(a) => a.evaluate(lookup).map(|result| !result),
+            Expression::Missing(name) => {
+                lookup(name).map(|value| value.missing != Missing::NOT_MISSING)
+            }
+            Expression::SystemMissing(name) => {
+                lookup(name).map(|value| value.missing == Missing::SYSTEM_MISSING)
+            }
+            Expression::Compare(operator, left, right) => {
+                let ordering = match (left.resolve(lookup)?, right.resolve(lookup)?) {
+                    (Resolved::Number(a), Resolved::Number(b)) => a.partial_cmp(&b)?,
+                    (Resolved::Number(a), Resolved::Text(b)) => a.to_string().cmp(&b),
+                    (Resolved::Text(a), Resolved::Number(b)) => a.cmp(&b.to_string()),
+                    (Resolved::Text(a), Resolved::Text(b)) => a.cmp(&b),
+                };
+
+                Some(match operator {
+                    Operator::Eq => ordering == Ordering::Equal,
+                    Operator::Ne => ordering != Ordering::Equal,
+                    Operator::Lt => ordering == Ordering::Less,
+                    Operator::Le => ordering != Ordering::Greater,
+                    Operator::Gt => ordering == Ordering::Greater,
+                    Operator::Ge => ordering != Ordering::Less,
+                })
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn resolve<'a, F>(&self, lookup: &F) -> Option<Resolved>
+    where
+        F: Fn(&str) -> Option<&'a Value>,
+    {
+        match self {
+            Operand::Number(number) => Some(Resolved::Number(*number)),
+            Operand::Text(text) => Some(Resolved::Text(text.clone())),
+            Operand::Variable(name) => {
+                let value = lookup(name)?;
+
+                if value.missing != Missing::NOT_MISSING {
+                    return None;
+                }
+
+                Some(match value.value.as_f64() {
+                    Some(number) => Resolved::Number(number),
+                    None => Resolved::Text(format!("{}", value.value)),
+                })
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(ref token) if *token == expected => Ok(()),
+            Some(token) => Err(format!("expected {:?}, found {:?}", expected, token)),
+            None => Err(format!("expected {:?}, found end of rule", expected)),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Name(name)) => name.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, String> {
+        if self.is_keyword("if") {
+            self.next();
+            let condition = self.expression()?;
+
+            if !self.is_keyword("then") {
+                return Err("expected then".to_string());
+            }
+            self.next();
+
+            let consequence = self.expression()?;
+            return Ok(Expression::If(Box::new(condition), Box::new(consequence)));
+        }
+
+        self.or()
+    }
+
+    fn or(&mut self) -> Result<Expression, String> {
+        let mut left = self.and()?;
+
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let right = self.and()?;
+            left = Expression::Or(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expression, String> {
+        let mut left = self.not()?;
+
+        while self.peek() == Some(&Token::And) {
+            self.next();
+            let right = self.not()?;
+            left = Expression::And(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expression, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Expression::Not(Box::new(self.not()?)));
+        }
+
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expression, String> {
+        if self.peek() == Some(&Token::LeftParen) {
+            self.next();
+            let expression = self.expression()?;
+            self.expect(Token::RightParen)?;
+            return Ok(expression);
+        }
+
+        for keyword in ["missing", "sysmiss"].iter() {
+            if self.is_keyword(keyword)
+                && self.tokens.get(self.position + 1) == Some(&Token::LeftParen)
+            {
+                self.position += 2;
+
+                let name = match self.next() {
+                    Some(Token::Name(name)) => name,
+                    _ => return Err(format!("{}() expects a variable name", keyword)),
+                };
+                self.expect(Token::RightParen)?;
+
+                return Ok(match *keyword {
+                    "missing" => Expression::Missing(name),
+                    _ => Expression::SystemMissing(name),
+                });
+            }
+        }
+
+        let left = self.operand()?;
+        let operator = match self.next() {
+            Some(Token::Operator(operator)) => operator,
+            Some(token) => return Err(format!("expected a comparison, found {:?}", token)),
+            None => return Err("expected a comparison, found end of rule".to_string()),
+        };
+        let right = self.operand()?;
+
+        Ok(Expression::Compare(operator, left, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Operand::Number(number)),
+            Some(Token::Text(text)) => Ok(Operand::Text(text)),
+            Some(Token::Name(name)) => Ok(Operand::Variable(name)),
+            Some(token) => Err(format!("expected a value or variable, found {:?}", token)),
+            None => Err("expected a value or variable, found end of rule".to_string()),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LeftParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RightParen);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let text = take_while(&mut chars, |ch| ch != c);
+
+                if chars.next() != Some(c) {
+                    return Err(format!("unterminated string {}{}", c, text));
+                }
+                tokens.push(Token::Text(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let followed_by_eq = chars.peek() == Some(&'=');
+                if followed_by_eq {
+                    chars.next();
+                }
+
+                tokens.push(match (c, followed_by_eq) {
+                    ('=', _) => Token::Operator(Operator::Eq),
+                    ('!', true) => Token::Operator(Operator::Ne),
+                    ('!', false) => Token::Not,
+                    ('<', true) => Token::Operator(Operator::Le),
+                    ('<', false) => Token::Operator(Operator::Lt),
+                    ('>', true) => Token::Operator(Operator::Ge),
+                    _ => Token::Operator(Operator::Gt),
+                });
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    return Err(format!("expected {}{}", c, c));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            _ if c.is_ascii_digit() || c == '-' || c == '.' => {
+                chars.next();
+                let number = format!(
+                    "{}{}",
+                    c,
+                    take_while(&mut chars, |ch| ch.is_ascii_digit() || ch == '.')
+                );
+
+                match number.parse::<f64>() {
+                    Ok(number) => tokens.push(Token::Number(number)),
+                    Err(_) => return Err(format!("invalid number {}", number)),
+                }
+            }
+            _ if is_name_character(c) => {
+                let name = take_while(&mut chars, is_name_character);
+
+                tokens.push(match name.to_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Name(name),
+                });
+            }
+            _ => return Err(format!("unexpected character {}", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Characters allowed in SPSS, Stata and SAS variable names
+fn is_name_character(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == '@' || c == '#' || c == '$'
+}
+
+fn take_while<F>(chars: &mut Peekable<Chars>, predicate: F) -> String
+where
+    F: Fn(char) -> bool,
+{
+    let mut result = String::new();
+
+    while let Some(&c) = chars.peek() {
+        if !predicate(c) {
+            break;
+        }
+        result.push(c);
+        chars.next();
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn row(values: Vec<(&str, &str)>) -> HashMap<String, Value> {
+        values
+            .into_iter()
+            .map(|(name, value)| {
+                let mut v = Value::from(value);
+                if value.is_empty() {
+                    v.missing = Missing::SYSTEM_MISSING;
+                }
+                (name.to_string(), v)
+            })
+            .collect()
+    }
+
+    fn evaluate(rule: &str, values: Vec<(&str, &str)>) -> Option<bool> {
+        let row = row(values);
+        Expression::parse(rule)
+            .unwrap()
+            .evaluate(&|name: &str| row.get(name))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Expression::parse("if employed == 2 then missing(hours)"),
+            Ok(Expression::If(
+                Box::new(Expression::Compare(
+                    Operator::Eq,
+                    Operand::Variable("employed".to_string()),
+                    Operand::Number(2.0)
+                )),
+                Box::new(Expression::Missing("hours".to_string()))
+            ))
+        );
+
+        assert_eq!(
+            Expression::parse("a < b and not c >= -1.5 or d != 'x'")
+                .unwrap()
+                .variables(),
+            vec!["a", "b", "c", "d"]
+        );
+
+        assert!(Expression::parse("employed ==").is_err());
+        assert!(Expression::parse("employed").is_err());
+        assert!(Expression::parse("(a == 1").is_err());
+        assert!(Expression::parse("a == 'x").is_err());
+        assert!(Expression::parse("if a == 1 missing(b)").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let rule = "if employed == 2 then missing(hours)";
+        assert_eq!(evaluate(rule, vec![("employed", "2"), ("hours", "")]), Some(true));
+        assert_eq!(evaluate(rule, vec![("employed", "2"), ("hours", "40")]), Some(false));
+        assert_eq!(evaluate(rule, vec![("employed", "1"), ("hours", "40")]), Some(true));
+        assert_eq!(evaluate(rule, vec![("employed", ""), ("hours", "40")]), None);
+
+        let rule = "youngest_child < age";
+        assert_eq!(evaluate(rule, vec![("youngest_child", "9"), ("age", "30")]), Some(true));
+        assert_eq!(evaluate(rule, vec![("youngest_child", "31"), ("age", "30")]), Some(false));
+        assert_eq!(evaluate(rule, vec![("youngest_child", ""), ("age", "30")]), None);
+
+        let rule = "sex = 'f' || sysmiss(sex) && !(pregnant == 1)";
+        assert_eq!(evaluate(rule, vec![("sex", "f"), ("pregnant", "1")]), Some(true));
+        assert_eq!(evaluate(rule, vec![("sex", "m"), ("pregnant", "1")]), Some(false));
+        assert_eq!(evaluate(rule, vec![("sex", ""), ("pregnant", "")]), None);
+        assert_eq!(evaluate(rule, vec![("sex", ""), ("pregnant", "2")]), Some(true));
+    }
+}
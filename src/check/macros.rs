@@ -4,22 +4,65 @@ macro_rules! include_check(($summary:expr, $check_name:expr, $desc:expr, $catego
                                                Status::new($desc, $category));
                            }));
 
-macro_rules! include_locators(($config:expr,
-                               $status:expr,
-                               $variable_name:expr,
-                               $variable_index:expr,
-                               $value_index:expr) =>
+macro_rules! include_locators {
+    ($config:expr,
+     $status:expr,
+     $variable_name:expr,
+     $variable_index:expr,
+     $value_index:expr,
+     note: $note:expr) =>
+    (if let Some(metadata_only) = $config.metadata_only {
+        if !metadata_only {
+            let locator = Locator::new($variable_name.clone(),
+                                       $variable_index,
+                                       $value_index)
+                .with_note(&$note);
+            $status.add_locator(locator, $config.max_locators());
+        }
+    });
+    ($config:expr,
+     $status:expr,
+     $variable_name:expr,
+     $variable_index:expr,
+     $value_index:expr) =>
     (if let Some(metadata_only) = $config.metadata_only {
         if !metadata_only {
             let locator = Locator::new($variable_name.clone(),
                                        $variable_index,
                                        $value_index);
-            if let Some(ref mut locators) = $status.locators {
-                locators.insert(locator);
-            } else {
-                let mut set = HashSet::new();
-                set.insert(locator);
-                $status.locators = Some(set);
-            }
+            $status.add_locator(locator, $config.max_locators());
+        }
+    });
+    ($config:expr,
+     $status:expr,
+     $variable_name:expr,
+     $variable_index:expr,
+     $value_index:expr,
+     $value:expr) =>
+    (if let Some(metadata_only) = $config.metadata_only {
+        if !metadata_only {
+            let locator = Locator::new($variable_name.clone(),
+                                       $variable_index,
+                                       $value_index)
+                .with_value(&$value);
+            $status.add_locator(locator, $config.max_locators());
+        }
+    });
+    ($config:expr,
+     $status:expr,
+     $variable_name:expr,
+     $variable_index:expr,
+     $value_index:expr,
+     $value:expr,
+     $note:expr) =>
+    (if let Some(metadata_only) = $config.metadata_only {
+        if !metadata_only {
+            let locator = Locator::new($variable_name.clone(),
+                                       $variable_index,
+                                       $value_index)
+                .with_value(&$value)
+                .with_note(&$note);
+            $status.add_locator(locator, $config.max_locators());
         }
-    }));
+    });
+}
@@ -12,7 +12,11 @@ use std::io::prelude::*;
 #[macro_use]
 mod macros;
 mod dictionary;
+pub mod expression;
+mod outliers;
 pub mod post;
+pub mod row;
+mod suda;
 pub mod value;
 pub mod variable;
 
@@ -20,6 +24,7 @@ type CheckFn<T> = fn(value: &T, config: &Config, report: &mut Report);
 
 pub type VariableCheckFn = CheckFn<Variable>;
 pub type ValueCheckFn = CheckFn<Value>;
+pub type RowCheckFn = fn(row: &[Value], config: &Config, report: &mut Report);
 pub type PostCheckFn = fn(context: &mut Context);
 
 #[derive(Debug, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize)]
@@ -38,18 +43,34 @@ pub enum CheckName {
     ValueLabelSpellcheck,
 
     ValueDefinedMissingNoLabel,
+    ValuesWithoutLabels,
+    UnusedValueLabels,
+    MissingCodeConsistency,
+    MeasureConsistency,
 
     // Data Integrity
     DuplicateValues,
+    DuplicateCompoundKeys,
+    DuplicateRecords,
+    ValueOutOfRange,
+    NumericOutliers,
+    ConsistencyRules,
 
     StringValueOddCharacters,
     StringValueSpellcheck,
+    InvisibleCharacters,
+    Mojibake,
     SystemMissingOverThreshold,
+    RecordMissingOverThreshold,
+    ConstantVariables,
 
     // Disclosure Risk
     DateFormat,
     ValueRegexPatterns,
     VariablesWithUniqueValues,
+    KAnonymity,
+    LDiversity,
+    SpecialUniques,
 
     StringValueStopword,
 }
@@ -67,6 +88,7 @@ impl fmt::Display for CheckName {
 pub struct Check {
     pub variable: Vec<VariableCheckFn>,
     pub value: Vec<ValueCheckFn>,
+    pub row: Vec<RowCheckFn>,
     pub post: Vec<PostCheckFn>,
 }
 
@@ -75,6 +97,7 @@ impl Check {
         Check {
             variable: variable::register(),
             value: value::register(),
+            row: row::register(),
             post: post::register(),
         }
     }
@@ -84,9 +107,10 @@ impl fmt::Debug for Check {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "{{ variable: {}, value: {} }}",
+            "{{ variable: {}, value: {}, row: {} }}",
             self.variable.len(),
-            self.value.len()
+            self.value.len(),
+            self.row.len()
         )
     }
 }
@@ -98,6 +122,117 @@ pub fn contains(string: &str, patterns: &Vec<String>) -> bool {
         .fold(false, |a, b| a || b)
 }
 
+/// Kinds of stray whitespace and invisible characters found in the text
+pub fn invisible_characters(text: &str) -> Vec<&'static str> {
+    let mut kinds = vec![];
+
+    if text.starts_with(char::is_whitespace) {
+        kinds.push("leading whitespace");
+    }
+
+    if text.ends_with(char::is_whitespace) {
+        kinds.push("trailing whitespace");
+    }
+
+    let mut push = |kind| {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    };
+
+    for c in text.chars() {
+        match c {
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' => push("non-breaking space"),
+            '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' => push("zero-width character"),
+            '\u{00AD}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' => {
+                push("invisible formatting character")
+            }
+            _ if c.is_control() => push("control character"),
+            _ => (),
+        }
+    }
+
+    kinds
+}
+
+/// Finds the first sequence typical of UTF-8 text decoded as Latin-1 or
+/// Windows-1252, e.g. "Ã©" for "é" or "â€™" for "’", or a U+FFFD replacement
+/// character left by a failed conversion
+pub fn mojibake(text: &str) -> Option<String> {
+    // characters Windows-1252 decodes the bytes 0x80 to 0x9F to
+    const WINDOWS_1252: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+    let chars = text.chars().collect::<Vec<char>>();
+
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '\u{FFFD}' {
+            return Some(c.to_string());
+        }
+
+        let next = match chars.get(i + 1) {
+            Some(next) => *next,
+            None => break,
+        };
+
+        // lead bytes of 2 byte sequences (U+0080 to U+07FF) and of the
+        // 3 byte sequences for punctuation (U+2000 to U+2FFF)
+        let is_lead = match *c {
+            'Â' | 'Ã' | 'Å' | 'Æ' | 'Ë' | 'Î' | 'Ð' | 'Ñ' => true,
+            'â' => next == '€' || next == '\u{0080}',
+            _ => false,
+        };
+        let is_continuation = ('\u{0080}'..='\u{00BF}').contains(&next) || WINDOWS_1252.contains(next);
+
+        if is_lead && is_continuation {
+            let end = if *c == 'â' { (i + 3).min(chars.len()) } else { i + 2 };
+            return Some(chars[i..end].iter().collect());
+        }
+    }
+
+    None
+}
+
+/// Note for a mojibake locator, giving the encoding the file declared so it
+/// can be compared with the encoding the text was really written in
+pub fn mojibake_note(sequence: &str, source: &str, file_encoding: &Option<String>) -> String {
+    format!(
+        "{:?} in {}, file encoding {}",
+        sequence,
+        source,
+        file_encoding.as_ref().map_or("not declared", |e| e.as_str())
+    )
+}
+
+/// Match a name against a glob pattern, `*` matches any run of characters
+/// and `?` any single character
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some(&'*'), _) => {
+                matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+            }
+            (Some(&'?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let name = name.chars().collect::<Vec<char>>();
+
+    matches(&pattern, &name)
+}
+
+/// Format a combination of values, e.g. "1043, 2"
+pub fn join_values(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{}", v.value))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
 fn read_file(path: &str) -> io::Result<String> {
     let mut f = File::open(path)?;
 
@@ -135,6 +270,33 @@ fn capitalize(s: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mojibake() {
+        assert_eq!(mojibake("café, naïve, “quoted”"), None);
+        assert_eq!(mojibake("cafÃ©"), Some("Ã©".to_string()));
+        assert_eq!(mojibake("donâ€™t"), Some("â€™".to_string()));
+        assert_eq!(mojibake("Â£5"), Some("Â£".to_string()));
+        assert_eq!(mojibake("bad \u{FFFD} byte"), Some("\u{FFFD}".to_string()));
+        assert_eq!(mojibake("Ã"), None);
+    }
+
+    #[test]
+    fn test_invisible_characters() {
+        assert!(invisible_characters("fine text").is_empty());
+        assert_eq!(
+            invisible_characters(" padded\t"),
+            vec!["leading whitespace", "trailing whitespace", "control character"]
+        );
+        assert_eq!(
+            invisible_characters("no\u{00A0}break\u{200B}"),
+            vec!["non-breaking space", "zero-width character"]
+        );
+        assert_eq!(
+            invisible_characters("soft\u{00AD}hyphen"),
+            vec!["invisible formatting character"]
+        );
+    }
+
     #[test]
     fn test_contains() {
         let patterns = vec!["bar".to_string()];
@@ -143,6 +305,22 @@ mod tests {
         assert_eq!(contains("foo baz qux", &patterns), false);
     }
 
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("age", "age"));
+        assert!(glob_match("q*_score", "q12_score"));
+        assert!(glob_match("q?", "q1"));
+        assert_eq!(glob_match("q?", "q12"), false);
+        assert_eq!(glob_match("age", "wage"), false);
+    }
+
+    #[test]
+    fn test_join_values() {
+        let values = vec![Value::from("1043"), Value::from("2")];
+
+        assert_eq!(join_values(&values), "1043, 2");
+    }
+
     #[test]
     fn test_to_sentence() {
         assert_eq!(to_sentence("ThisIsASentence"), "This is a sentence");
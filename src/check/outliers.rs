@@ -0,0 +1,103 @@
+use config::OutlierMethod;
+
+use std::cmp::Ordering;
+
+/// Lower and upper fences outside of which a value is an outlier, `None`
+/// when there are too few values or they have no spread. NaN and infinite
+/// values are ignored.
+pub fn fences(values: &[f64], method: &OutlierMethod, threshold: f64) -> Option<(f64, f64)> {
+    let mut sorted = values
+        .iter()
+        .cloned()
+        .filter(|x| x.is_finite())
+        .collect::<Vec<f64>>();
+
+    if sorted.len() < 2 {
+        return None;
+    }
+
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let (centre_low, centre_high, spread) = match method {
+        OutlierMethod::Iqr => {
+            let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+            (q1, q3, q3 - q1)
+        }
+        OutlierMethod::ZScore => {
+            let n = sorted.len() as f64;
+            let mean = sorted.iter().sum::<f64>() / n;
+            let variance = sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (mean, mean, variance.sqrt())
+        }
+        OutlierMethod::Mad => {
+            let median = quantile(&sorted, 0.5);
+            let mut deviations = sorted
+                .iter()
+                .map(|x| (x - median).abs())
+                .collect::<Vec<f64>>();
+            deviations.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+            // scaled so the MAD estimates the standard deviation of normal data
+            (median, median, quantile(&deviations, 0.5) / 0.6745)
+        }
+    };
+
+    if spread <= 0.0 {
+        return None;
+    }
+
+    Some((centre_low - threshold * spread, centre_high + threshold * spread))
+}
+
+/// Quantile of sorted values, interpolating linearly between closest ranks
+fn quantile(sorted: &[f64], p: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * p;
+    let low = h.floor() as usize;
+    let high = (low + 1).min(sorted.len() - 1);
+
+    sorted[low] + (h - low as f64) * (sorted[high] - sorted[low])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Vec<f64> {
+        let mut values = (1..=10).map(|i| i as f64).collect::<Vec<f64>>();
+        values.push(9999999.0);
+        values
+    }
+
+    #[test]
+    fn test_quantile() {
+        let sorted = vec![1.0, 2.0, 3.0, 4.0];
+        assert_eq!(quantile(&sorted, 0.0), 1.0);
+        assert_eq!(quantile(&sorted, 0.5), 2.5);
+        assert_eq!(quantile(&sorted, 1.0), 4.0);
+    }
+
+    #[test]
+    fn test_fences() {
+        assert_eq!(
+            fences(&values(), &OutlierMethod::Iqr, 1.5),
+            Some((-4.0, 16.0))
+        );
+
+        let (low, high) = fences(&values(), &OutlierMethod::Mad, 3.5).unwrap();
+        assert!(low < 1.0 && high > 10.0 && high < 100.0);
+
+        let (low, high) = fences(&values(), &OutlierMethod::ZScore, 2.0).unwrap();
+        assert!(low < 1.0 && high < 9999999.0);
+
+        assert_eq!(fences(&[5.0, 5.0, 5.0], &OutlierMethod::ZScore, 3.0), None);
+        assert_eq!(fences(&[5.0], &OutlierMethod::Iqr, 1.5), None);
+
+        let mut with_non_finite = values();
+        with_non_finite.extend(vec![::std::f64::NAN, ::std::f64::INFINITY]);
+        assert_eq!(
+            fences(&with_non_finite, &OutlierMethod::Iqr, 1.5),
+            Some((-4.0, 16.0))
+        );
+        assert_eq!(fences(&[5.0, ::std::f64::NAN], &OutlierMethod::Mad, 3.5), None);
+    }
+}
@@ -1,12 +1,17 @@
-use check::{contains, PostCheckFn};
+use check::{glob_match, invisible_characters, join_values, mojibake, mojibake_note, PostCheckFn};
 use check::dictionary::{dictionary, spellcheck_predicate, stopword_predicate};
-use model::variable::{Variable, VariableType};
+use check::outliers;
+use check::suda;
+use config::MissingType;
+use model::variable::{Measure, Variable, VariableType};
 use model::anyvalue::AnyValue;
 use model::missing::Missing;
+use model::value::Value;
 use readstat::context::Context;
-use report::{Category, Locator, Status};
+use report::{Category, Locator, MissingPercentages, RecordRisk, Status};
 
-use std::collections::{HashSet, HashMap};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 use regex::Regex;
 
@@ -22,18 +27,31 @@ pub fn register() -> Vec<PostCheckFn> {
         value_label_odd_characters,
         value_label_max_length,
         value_label_spellcheck,
+        values_without_labels,
+        unused_value_labels,
+        value_label_invisible_characters,
+        value_label_mojibake,
+        missing_code_consistency,
+        measure_consistency,
 
         variable_label_spellcheck,
 
         // Data Integrity
         duplicate_values,
+        duplicate_compound_keys,
+        duplicate_records,
+        numeric_outliers,
         string_value_odd_characters,
         system_missing_over_threshold,
+        constant_variables,
         string_value_spellcheck,
 
         //  Disclosure Risk
         regex_patterns,
         unique_values,
+        k_anonymity,
+        l_diversity,
+        special_uniques,
         string_value_stopword,
     ]
 }
@@ -51,13 +69,14 @@ fn bad_filename(context: &mut Context) {
 
         use check::CheckName::BadFileName;
         let mut status = Status::new(&bad_filename.desc, Category::BasicFile);
-        let mut locators: HashSet<Locator> = HashSet::new();
 
         if !re.is_match(file_name) {
             status.fail += 1;
 
-            locators.insert(Locator::new("".to_string(), -1, -1));
-            status.locators = Some(locators);
+            status.add_locator(
+                Locator::new("".to_string(), -1, -1).with_value(file_name),
+                config.max_locators(),
+            );
         } else {
             status.pass += 1;
         }
@@ -108,7 +127,7 @@ fn value_label_odd_characters(context: &mut Context) {
             for variable in (*context).variables.iter() {
                 if let Some(value_labels) = (*context).value_labels.get(&variable.value_labels) {
                     for (_value, label) in value_labels.iter() {
-                        if contains(label, &setting.setting) {
+                        if let Some(found) = setting.setting.find(label) {
                             status.fail += 1;
 
                             include_locators!(
@@ -116,7 +135,9 @@ fn value_label_odd_characters(context: &mut Context) {
                                 status,
                                 variable.name,
                                 variable.index,
-                                -1
+                                -1,
+                                label,
+                                found
                             );
                         } else {
                             status.pass += 1;
@@ -153,7 +174,8 @@ fn value_label_max_length(context: &mut Context) {
                                 status,
                                 value.variable.name,
                                 value.variable.index,
-                                -1
+                                -1,
+                                value.label
                             );
                         }
                     }
@@ -182,115 +204,204 @@ fn value_label_spellcheck(context: &mut Context) {
     }
 
     let words: HashMap<String, Locator> = mapping.iter()
-        .map(|(k, v)| (k.clone(), Locator::from(v.clone())))
+        .map(|(k, v)| (k.clone(), Locator::from(v.clone()).with_value(k)))
         .collect();
 
     dictionary(context, ValueLabelSpellcheck, &words, spellcheck_predicate);
 }
 
-/// Spellcheck variable labels
-fn variable_label_spellcheck(context: &mut Context) {
-    use check::CheckName::VariableLabelSpellcheck;
-
-    let mut words: HashMap<String, Locator> = HashMap::new();
-
-    for variable in context.variables.iter() {
-        words.insert(variable.label.clone(), Locator::from(variable));
-    }
-
-    dictionary(context, VariableLabelSpellcheck, &words, spellcheck_predicate);
-}
+/// Check the values of labelled variables have a label. Each unlabelled
+/// code is reported once per variable with its frequency.
+fn values_without_labels(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
 
-/// Spellcheck string values
-fn string_value_spellcheck(context: &mut Context) {
-    use check::CheckName::StringValueSpellcheck;
+    if let Some(ref setting) = config.metadata.values_without_labels {
+        use check::CheckName::ValuesWithoutLabels;
+        include_check!(
+            report.summary,
+            ValuesWithoutLabels,
+            &setting.desc,
+            Category::Metadata
+        );
 
-    let variables: Vec<Variable> = context.variables.iter()
-        .filter(|v| v.type_ == VariableType::Text)
-        .map(|v| v.clone())
-        .collect();
+        if !setting.setting {
+            return;
+        }
 
-    let mut words: HashMap<String, Locator> = HashMap::new();
-    for var in variables {
-        if let Some(occurrences) = context.frequency_table.get(&var) {
-            for (val, _occ) in occurrences.iter() {
-                let mut locator = Locator::from(&var);
-                locator.value_index = val.row;
+        if let Some(ref mut status) = report.summary.get_mut(&ValuesWithoutLabels) {
+            for variable in context.variables.iter() {
+                let labels = match context.value_labels.get(&variable.value_labels) {
+                    Some(labels) => labels,
+                    None => continue,
+                };
+
+                if let Some(values) = context.frequency_table.get(&variable) {
+                    for (value, rows) in values
+                        .iter()
+                        .filter(|(v, _)| v.missing == Missing::NOT_MISSING)
+                    {
+                        if labels.contains_key(&format!("{}", value.value)) {
+                            status.pass += 1;
+                        } else {
+                            status.fail += 1;
 
-                words.insert(val.value.to_string(), locator.clone());
+                            include_locators!(
+                                config,
+                                status,
+                                variable.name,
+                                variable.index,
+                                -1,
+                                value.value,
+                                format!("unlabelled, occurs {} times", rows.len())
+                            );
+                        }
+                    }
+                }
             }
         }
     }
-
-    dictionary(context, StringValueSpellcheck, &words, spellcheck_predicate);
 }
 
-/// Notify if a variable has duplicate values, and where they are
-fn duplicate_values(context: &mut Context) {
+/// Check every value label is used by a value of a variable the label set
+/// is attached to, and every label set is attached to a variable
+fn unused_value_labels(context: &mut Context) {
     let (config, report) = (&context.config, &mut context.report);
 
-    if let Some(ref setting) = config.data_integrity.duplicate_values {
-        use check::CheckName::DuplicateValues;
+    if let Some(ref setting) = config.metadata.unused_value_labels {
+        use check::CheckName::UnusedValueLabels;
         include_check!(
             report.summary,
-            DuplicateValues,
-            format!("{} (On variables {:?})", setting.desc, setting.setting).as_str(),
-            Category::DataIntegrity
+            UnusedValueLabels,
+            &setting.desc,
+            Category::Metadata
         );
 
-        if let Some(ref mut status) = report.summary.get_mut(&DuplicateValues) {
-            let case_count = &report.metadata.raw_case_count;
+        if !setting.setting {
+            return;
+        }
 
-            context
-                .frequency_table
-                .iter()
-                .filter(move |(variable, _)| setting.setting.contains(&variable.name))
-                .for_each(|(variable, map)| {
-                    let count = map.values().filter(|occ| **occ == 1).count() as i32;
-                    if count != *case_count {
+        let frequency_table = &context.frequency_table;
+
+        if let Some(ref mut status) = report.summary.get_mut(&UnusedValueLabels) {
+            for (label_set, labels) in context.value_labels.iter() {
+                let variables = context
+                    .variables
+                    .iter()
+                    .filter(|v| &v.value_labels == label_set)
+                    .collect::<Vec<&Variable>>();
+
+                if variables.is_empty() {
+                    status.fail += 1;
+
+                    include_locators!(
+                        config,
+                        status,
+                        String::new(),
+                        -1,
+                        -1,
+                        label_set,
+                        "label set is not attached to any variable"
+                    );
+                    continue;
+                }
+
+                let used = variables
+                    .iter()
+                    .filter_map(|v| frequency_table.get(*v))
+                    .flat_map(|values| values.keys().map(|value| format!("{}", value.value)))
+                    .collect::<HashSet<String>>();
+
+                let names = variables
+                    .iter()
+                    .map(|v| v.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+
+                for (code, label) in labels.iter() {
+                    if used.contains(code) {
+                        status.pass += 1;
+                    } else {
                         status.fail += 1;
 
-                        include_locators!(config, status, variable.name, variable.index, -1);
+                        include_locators!(
+                            config,
+                            status,
+                            names,
+                            variables[0].index,
+                            -1,
+                            code,
+                            format!("label \"{}\" is never used", label)
+                        );
                     }
-                });
-
-            status.pass = setting.setting.len() as i32 - status.fail;
+                }
+            }
         }
     }
 }
 
-fn string_value_odd_characters(context: &mut Context) {
+/// Names and indices of the variables using a value label set, or the name
+/// of the label set itself when no variable uses it
+fn label_set_variables(variables: &[Variable], label_set: &str) -> Vec<(String, i32)> {
+    let mut using = variables
+        .iter()
+        .filter(|v| v.value_labels == label_set)
+        .map(|v| (v.name.clone(), v.index))
+        .collect::<Vec<_>>();
+
+    if using.is_empty() {
+        using.push((label_set.to_string(), -1));
+    }
+
+    using.sort_by_key(|&(_, index)| index);
+    using
+}
+
+/// Check value labels for stray whitespace and invisible characters, each
+/// kind found is noted on the locator
+fn value_label_invisible_characters(context: &mut Context) {
     let (config, report) = (&context.config, &mut context.report);
 
-    if let Some(ref setting) = config.data_integrity.string_value_odd_characters {
-        use check::CheckName::StringValueOddCharacters;
+    if let Some(ref setting) = config.data_integrity.invisible_characters {
+        use check::CheckName::InvisibleCharacters;
         include_check!(
             report.summary,
-            StringValueOddCharacters,
-            format!("{} {:?}", setting.desc, &setting.setting).as_str(),
+            InvisibleCharacters,
+            &setting.desc,
             Category::DataIntegrity
         );
 
-        if let Some(ref mut status) = report.summary.get_mut(&StringValueOddCharacters) {
-            for variable in (*context).variables.iter() {
-                if let Some(values) = (*context).frequency_table.get(&variable) {
-                    for (value, _occ) in values.iter().filter(|(v, _)| match v.value {
-                        AnyValue::Str(_) => true,
-                        _ => false,
-                    }) {
-                        if contains(&format!("{}", &value.value), &setting.setting) {
-                            status.fail += 1;
+        if !setting.setting {
+            return;
+        }
 
-                            include_locators!(
-                                config,
-                                status,
-                                value.variable.name,
-                                value.variable.index,
-                                value.row
-                            );
-                        } else {
-                            status.pass += 1;
-                        }
+        if let Some(ref mut status) = report.summary.get_mut(&InvisibleCharacters) {
+            let mut label_sets = context.value_labels.iter().collect::<Vec<_>>();
+            label_sets.sort_by_key(|(name, _)| *name);
+
+            for (label_set, labels) in label_sets {
+                let variables = label_set_variables(&context.variables, label_set);
+
+                for (code, label) in labels.iter() {
+                    let kinds = invisible_characters(label);
+
+                    if kinds.is_empty() {
+                        status.pass += 1;
+                        continue;
+                    }
+
+                    status.fail += 1;
+
+                    // located on every variable sharing the label set
+                    for (name, index) in variables.iter() {
+                        include_locators!(
+                            config,
+                            status,
+                            name,
+                            *index,
+                            -1,
+                            format!("{:?}", label),
+                            format!("{} in value label {}", kinds.join(", "), code)
+                        );
                     }
                 }
             }
@@ -298,127 +409,281 @@ fn string_value_odd_characters(context: &mut Context) {
     }
 }
 
-/// Report variables with a number of system missing values over a
-/// specified threhold.
-fn system_missing_over_threshold(context: &mut Context) {
+/// Check value labels for mojibake, noting the declared file encoding
+fn value_label_mojibake(context: &mut Context) {
     let (config, report) = (&context.config, &mut context.report);
 
-    if let Some(ref setting) = config.data_integrity.system_missing_value_threshold {
-        use check::CheckName::SystemMissingOverThreshold;
+    if let Some(ref setting) = config.data_integrity.mojibake {
+        use check::CheckName::Mojibake;
         include_check!(
             report.summary,
-            SystemMissingOverThreshold,
-            format!("{} (Threshold: {}%)", setting.desc, setting.setting).as_str(),
+            Mojibake,
+            &setting.desc,
             Category::DataIntegrity
         );
 
-        if let Some(ref mut status) = report.summary.get_mut(&SystemMissingOverThreshold) {
-            // map between variable and % missing
+        if !setting.setting {
+            return;
+        }
 
-            // pull count of sysmiss values from Context.frequency_table
-            // sum to percentage of sysmiss (delivered as NaN)
+        let file_encoding = report.metadata.file_encoding.clone();
 
-            for (variable, map) in &context.frequency_table {
-                let sum = map.iter().fold(0, |mut sum, (_, occ)| {
-                    sum += occ;
-                    sum
-                });
+        if let Some(ref mut status) = report.summary.get_mut(&Mojibake) {
+            let mut label_sets = context.value_labels.iter().collect::<Vec<_>>();
+            label_sets.sort_by_key(|(name, _)| *name);
 
-                assert_eq!(
-                    report.metadata.raw_case_count, sum,
-                    "case_count {} does not align with sum {} for variable {}",
-                    report.metadata.raw_case_count, sum, variable.name
-                );
+            for (label_set, labels) in label_sets {
+                let variables = label_set_variables(&context.variables, label_set);
 
-                // compare with config threhold
-                // and increment pass/fail
-                if let Some((_, count)) = map
-                    .iter()
-                    .find(|(value, _)| value.missing == Missing::SYSTEM_MISSING)
-                {
-                    let sys_miss = (*count as f32 / sum as f32) * 100.0;
-                    if sys_miss > setting.setting as f32 {
-                        status.fail += 1;
+                for (code, label) in labels.iter() {
+                    let sequence = match mojibake(label) {
+                        Some(sequence) => sequence,
+                        None => {
+                            status.pass += 1;
+                            continue;
+                        }
+                    };
+
+                    status.fail += 1;
 
-                        include_locators!(config, status, variable.name, variable.index, -1);
+                    for (name, index) in variables.iter() {
+                        include_locators!(
+                            config,
+                            status,
+                            name,
+                            *index,
+                            -1,
+                            format!("{:?}", label),
+                            mojibake_note(&sequence, &format!("value label {}", code), &file_encoding)
+                        );
                     }
                 }
             }
-
-            status.pass = report.metadata.variable_count - status.fail;
         }
     }
 }
 
-/// Flags values that match a regex pattern
-fn regex_patterns(context: &mut Context) {
+/// Check numeric variables with user-defined missing values all use the same
+/// missing codes, either the configured standard codes or the set of codes
+/// used by the most variables. Variables without missing codes are skipped.
+fn missing_code_consistency(context: &mut Context) {
     let (config, report) = (&context.config, &mut context.report);
 
-    if let Some(ref setting) = config.disclosure_risk.regex_patterns {
-        use check::CheckName::ValueRegexPatterns;
+    if let Some(ref setting) = config.metadata.missing_value_codes {
+        use check::CheckName::MissingCodeConsistency;
         include_check!(
             report.summary,
-            ValueRegexPatterns,
+            MissingCodeConsistency,
             &setting.desc,
-            Category::DisclosureRisk
+            Category::Metadata
         );
 
-        if let Some(ref mut status) = report.summary.get_mut(&ValueRegexPatterns) {
-            for variable in context.variables.iter() {
-                for (value, _occ) in context.frequency_table.get(&variable).unwrap() {
-                    for pattern in &setting.setting {
-                        let re = Regex::new(&pattern).unwrap();
+        let numeric_codes = |variable: &Variable| {
+            variable
+                .missing_ranges
+                .iter()
+                .filter_map(|range| match (range.lo.as_f64(), range.hi.as_f64()) {
+                    (Some(lo), Some(hi)) if !lo.is_nan() && !hi.is_nan() => Some((lo, hi)),
+                    _ => None,
+                })
+                .collect::<Vec<(f64, f64)>>()
+        };
+
+        let mut variables = context
+            .variables
+            .iter()
+            .filter(|v| v.type_ == VariableType::Numeric && !v.missing_ranges.is_empty())
+            .map(|v| {
+                let mut codes = numeric_codes(v);
+                codes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                (v, codes)
+            })
+            .collect::<Vec<_>>();
+        variables.sort_by_key(|(v, _)| v.index);
+
+        let (standard, source) = if !setting.setting.is_empty() {
+            let mut standard = setting
+                .setting
+                .iter()
+                .filter(|c| !c.is_nan())
+                .map(|c| (*c, *c))
+                .collect::<Vec<_>>();
+            standard.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            (standard, "the standard codes".to_string())
+        } else {
+            // the code set used by the most variables, the first read wins ties
+            let mut counts: Vec<(&Vec<(f64, f64)>, usize)> = vec![];
+            for (_, codes) in variables.iter() {
+                match counts.iter().position(|(c, _)| *c == codes) {
+                    Some(i) => counts[i].1 += 1,
+                    None => counts.push((codes, 1)),
+                }
+            }
 
-                        if re.is_match(&format!("{}", value.value)) || re.is_match(&value.label) {
-                            status.fail += 1;
+            match counts.iter().rev().max_by_key(|(_, count)| *count) {
+                Some((codes, count)) => (
+                    (*codes).clone(),
+                    format!("the codes of {} variables", count),
+                ),
+                None => (vec![], String::new()),
+            }
+        };
 
-                            include_locators!(
-                                config,
-                                status,
-                                value.variable.name,
-                                value.variable.index,
-                                value.row
-                            );
-                            break;
-                        }
+        let describe = |codes: &Vec<(f64, f64)>| {
+            codes
+                .iter()
+                .map(|(lo, hi)| {
+                    if lo == hi {
+                        format!("{}", lo)
+                    } else {
+                        format!("{} thru {}", lo, hi)
                     }
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        if let Some(ref mut status) = report.summary.get_mut(&MissingCodeConsistency) {
+            for (variable, codes) in variables.iter() {
+                if *codes == standard {
+                    status.pass += 1;
+                    continue;
                 }
-            }
 
-            status.pass = report.metadata.variable_count - status.fail;
+                status.fail += 1;
+
+                // the codes as declared, with their labels if they have one
+                let labels = context.value_labels.get(&variable.value_labels);
+                let declared = variable
+                    .missing_ranges
+                    .iter()
+                    .map(|range| {
+                        match labels.and_then(|labels| labels.get(&format!("{}", range.lo))) {
+                            Some(label) if range.lo == range.hi => format!("{} ({})", range, label),
+                            _ => format!("{}", range),
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                include_locators!(
+                    config,
+                    status,
+                    variable.name,
+                    variable.index,
+                    -1,
+                    declared,
+                    format!("differs from {}, {}", source, describe(&standard))
+                );
+            }
         }
     }
 }
 
-/// Count the number of variables with one or more unique values
-fn unique_values(context: &mut Context) {
+/// Check the measurement level of each variable is consistent with its
+/// labels and values: scale variables shouldn't be labelled like categorical
+/// variables, nominal variables shouldn't have too many distinct numeric
+/// values and string variables shouldn't be ordinal
+fn measure_consistency(context: &mut Context) {
     let (config, report) = (&context.config, &mut context.report);
 
-    if let Some(ref setting) = config.disclosure_risk.unique_values {
-        use check::CheckName::VariablesWithUniqueValues;
+    if let Some(ref setting) = config.metadata.measure_consistency {
+        use check::CheckName::MeasureConsistency;
         include_check!(
             report.summary,
-            VariablesWithUniqueValues,
+            MeasureConsistency,
             &setting.desc,
-            Category::DisclosureRisk
+            Category::Metadata
         );
 
-        if let Some(ref mut status) = report.summary.get_mut(&VariablesWithUniqueValues) {
-            for (variable, map) in context.frequency_table.iter() {
-                if let Some(_) = map.iter().find(|(_value, occ)| *occ <= &setting.setting) {
-                    status.fail += 1;
+        let thresholds = &setting.setting;
 
-                    include_locators!(config, status, variable.name, variable.index, -1);
-                } else {
-                    status.pass += 1
+        if let Some(ref mut status) = report.summary.get_mut(&MeasureConsistency) {
+            for variable in context.variables.iter() {
+                let values = context
+                    .frequency_table
+                    .get(variable)
+                    .map(|values| {
+                        values
+                            .keys()
+                            .filter(|v| v.missing == Missing::NOT_MISSING)
+                            .collect::<Vec<&Value>>()
+                    })
+                    .unwrap_or_default();
+
+                let problem = match (&variable.measure, &variable.type_) {
+                    (Measure::Scale, _) => {
+                        let labels = context.value_labels.get(&variable.value_labels);
+                        let labelled = values
+                            .iter()
+                            .filter(|v| {
+                                labels.map_or(false, |l| l.contains_key(&format!("{}", v.value)))
+                            })
+                            .count();
+
+                        let threshold = thresholds.scale_labelled_percentage as usize;
+
+                        if !values.is_empty() && labelled * 100 >= values.len() * threshold {
+                            Some(format!(
+                                "scale with {} of {} distinct values labelled",
+                                labelled,
+                                values.len()
+                            ))
+                        } else {
+                            None
+                        }
+                    }
+                    (Measure::Nominal, VariableType::Numeric)
+                        if values.len() > thresholds.nominal_max_distinct as usize =>
+                    {
+                        Some(format!("nominal with {} distinct values", values.len()))
+                    }
+                    (Measure::Ordinal, VariableType::Text) if thresholds.ordinal_strings => {
+                        Some("ordinal string".to_string())
+                    }
+                    _ => None,
+                };
+
+                match problem {
+                    Some(note) => {
+                        status.fail += 1;
+
+                        include_locators!(
+                            config,
+                            status,
+                            variable.name,
+                            variable.index,
+                            -1,
+                            format!("{:?}", variable.measure),
+                            note
+                        );
+                    }
+                    None => status.pass += 1,
                 }
             }
         }
     }
 }
 
-fn string_value_stopword(context: &mut Context) {
-    use check::CheckName::StringValueStopword;
+/// Spellcheck variable labels
+fn variable_label_spellcheck(context: &mut Context) {
+    use check::CheckName::VariableLabelSpellcheck;
+
+    let mut words: HashMap<String, Locator> = HashMap::new();
+
+    for variable in context.variables.iter() {
+        words.insert(
+            variable.label.clone(),
+            Locator::from(variable).with_value(&variable.label),
+        );
+    }
+
+    dictionary(context, VariableLabelSpellcheck, &words, spellcheck_predicate);
+}
+
+/// Spellcheck string values
+fn string_value_spellcheck(context: &mut Context) {
+    use check::CheckName::StringValueSpellcheck;
 
     let variables: Vec<Variable> = context.variables.iter()
         .filter(|v| v.type_ == VariableType::Text)
@@ -429,298 +694,2035 @@ fn string_value_stopword(context: &mut Context) {
     for var in variables {
         if let Some(occurrences) = context.frequency_table.get(&var) {
             for (val, _occ) in occurrences.iter() {
-                let mut locator = Locator::from(&var);
-                locator.value_index = val.row;
+                let locator = Locator::new(var.name.clone(), var.index, val.row)
+                    .with_value(&val.value);
 
                 words.insert(val.value.to_string(), locator.clone());
             }
         }
     }
 
-    //println!("words: {:#?}", &words);
-
-    dictionary(context, StringValueStopword, &words, stopword_predicate);
+    dictionary(context, StringValueSpellcheck, &words, spellcheck_predicate);
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Notify if a variable has duplicate values, and where they are. Each
+/// duplicated value is located on every row it occurs in.
+fn duplicate_values(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
 
-    use check::Check;
-    use config::{Config, Setting};
-    use model::anyvalue::AnyValue;
-    use model::value::Value;
-    use model::variable::Variable;
-    use report::Report;
+    if let Some(ref setting) = config.data_integrity.duplicate_values {
+        use check::CheckName::DuplicateValues;
+        include_check!(
+            report.summary,
+            DuplicateValues,
+            format!("{} (On variables {:?})", setting.desc, setting.setting).as_str(),
+            Category::DataIntegrity
+        );
 
-    use std::collections::HashMap;
-    // use report::anyvalue::AnyValue;
+        if let Some(ref mut status) = report.summary.get_mut(&DuplicateValues) {
+            context
+                .frequency_table
+                .iter()
+                .filter(move |(variable, _)| setting.setting.contains(&variable.name))
+                .for_each(|(variable, map)| {
+                    let duplicates = map
+                        .iter()
+                        .filter(|(_, rows)| rows.len() > 1)
+                        .collect::<Vec<_>>();
 
-    fn setup() -> Context {
-        let mut freq_table: HashMap<Variable, HashMap<Value, i32>> = HashMap::new();
+                    if !duplicates.is_empty() {
+                        status.fail += 1;
 
-        {
-            let mut temp: HashMap<Value, i32> = HashMap::new();
+                        for (value, rows) in duplicates {
+                            for row in rows {
+                                include_locators!(
+                                    config,
+                                    status,
+                                    variable.name,
+                                    variable.index,
+                                    *row,
+                                    value.value,
+                                    format!("occurs {} times", rows.len())
+                                );
+                            }
+                        }
+                    }
+                });
 
-            let mut qux = Value::from("qux");
-            qux.label = String::from("this is fine");
+            status.pass = setting.setting.len() as i32 - status.fail;
+        }
+    }
+}
 
-            let mut bar = Value::from("bar#");
-            bar.label = String::from("this@ is far too long to pss the test");
+/// Names which are not the name of any variable in the file
+fn unknown_variables<'a>(variables: &[Variable], names: &'a [String]) -> Vec<&'a String> {
+    names
+        .iter()
+        .filter(|name| !variables.iter().any(|v| &&v.name == name))
+        .collect()
+}
 
-            temp.insert(bar, 3);
-            temp.insert(Value::from("!baz"), 3);
-            temp.insert(qux, 4);
+/// Notify if a combination of variables has duplicate combined values.
+/// Each duplicated combination is located on every row it occurs in. Keys
+/// naming a variable not in the file fail, locating the missing variables.
+fn duplicate_compound_keys(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
 
-            let variable = Variable {
-                index: 0,
+    if let Some(ref setting) = config.data_integrity.duplicate_compound_keys {
+        use check::CheckName::DuplicateCompoundKeys;
+        include_check!(
+            report.summary,
+            DuplicateCompoundKeys,
+            format!("{} (On keys {:?})", setting.desc, setting.setting).as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&DuplicateCompoundKeys) {
+            for key in setting.setting.iter() {
+                let key_name = key.join(" + ");
+
+                // a key naming a variable not in the file can't be checked
+                let unknown = unknown_variables(&context.variables, key);
+                if !unknown.is_empty() {
+                    status.fail += 1;
+
+                    for name in unknown {
+                        include_locators!(
+                            config,
+                            status,
+                            name,
+                            -1,
+                            -1,
+                            note: format!("not found in the file, part of key {}", key_name)
+                        );
+                    }
+                    continue;
+                }
+
+                if let Some(occurrences) = context.tuples.get(key) {
+                    let duplicates = occurrences
+                        .iter()
+                        .filter(|(_, rows)| rows.len() > 1)
+                        .collect::<Vec<_>>();
+
+                    if duplicates.is_empty() {
+                        status.pass += 1;
+                        continue;
+                    }
+
+                    status.fail += 1;
+
+                    for (values, rows) in duplicates {
+                        for row in rows {
+                            include_locators!(
+                                config,
+                                status,
+                                key_name,
+                                -1,
+                                *row,
+                                join_values(values),
+                                format!("occurs {} times", rows.len())
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Notify if whole records are repeated, ignoring any excluded variables.
+/// Each group of identical records is located on every row in the group.
+fn duplicate_records(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.data_integrity.duplicate_records {
+        use check::CheckName::DuplicateRecords;
+        include_check!(
+            report.summary,
+            DuplicateRecords,
+            format!("{} (Excluding variables {:?})", setting.desc, setting.setting).as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&DuplicateRecords) {
+            // located on the variables the records are compared on
+            let record_variables = &context.record_variables;
+            let compared = context
+                .variables
+                .iter()
+                .filter(|v| {
+                    record_variables
+                        .as_ref()
+                        .and_then(|compared| compared.get(v.index as usize).cloned())
+                        .unwrap_or(true)
+                })
+                .map(|v| v.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(" + ");
+
+            let mut groups = context
+                .records
+                .iter()
+                .filter(|(_, rows)| rows.len() > 1)
+                .collect::<Vec<_>>();
+            groups.sort_by_key(|&(_, rows)| rows);
+
+            for (group, (values, rows)) in groups.iter().enumerate() {
+                let row_list = rows
+                    .iter()
+                    .map(|row| row.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                for row in rows.iter() {
+                    include_locators!(
+                        config,
+                        status,
+                        compared,
+                        -1,
+                        *row,
+                        values.join(", "),
+                        format!("group {}, identical records on rows {}", group + 1, row_list)
+                    );
+                }
+
+                status.fail += rows.len() as i32;
+            }
+
+            // both totals count rows
+            status.pass = context
+                .records
+                .values()
+                .filter(|rows| rows.len() == 1)
+                .count() as i32;
+        }
+    }
+}
+
+/// Locate the numeric values of each variable which fall outside the fences
+/// of the configured outlier method. Values are numeric when they parse,
+/// whatever the variable type, as CSV columns are all read as text.
+/// Variables without numeric values are skipped
+fn numeric_outliers(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.data_integrity.outliers {
+        use check::CheckName::NumericOutliers;
+        include_check!(
+            report.summary,
+            NumericOutliers,
+            format!(
+                "{} ({}, threshold {})",
+                setting.desc, setting.setting.method, setting.setting.threshold
+            )
+            .as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&NumericOutliers) {
+            let variables = context.variables.iter().filter(|v| {
+                setting.setting.variables.as_ref().map_or(true, |patterns| {
+                    patterns.iter().any(|pattern| glob_match(pattern, &v.name))
+                })
+            });
+
+            for variable in variables {
+                let observations = match context.frequency_table.get(variable) {
+                    Some(values) => values
+                        .iter()
+                        .filter(|(value, _rows)| value.missing == Missing::NOT_MISSING)
+                        .filter_map(|(value, rows)| value.value.as_f64().map(|n| (n, value, rows)))
+                        .filter(|(n, _value, _rows)| n.is_finite())
+                        .collect::<Vec<_>>(),
+                    None => continue,
+                };
+
+                if observations.is_empty() {
+                    continue;
+                }
+
+                let numbers = observations
+                    .iter()
+                    .flat_map(|(n, _value, rows)| rows.iter().map(move |_| *n))
+                    .collect::<Vec<f64>>();
+
+                let (low, high) = match outliers::fences(
+                    &numbers,
+                    &setting.setting.method,
+                    setting.setting.threshold,
+                ) {
+                    Some(fences) => fences,
+                    None => {
+                        status.pass += 1;
+                        continue;
+                    }
+                };
+
+                let outlying = observations
+                    .iter()
+                    .filter(|(n, _value, _rows)| *n < low || *n > high)
+                    .collect::<Vec<_>>();
+
+                if outlying.is_empty() {
+                    status.pass += 1;
+                    continue;
+                }
+
+                status.fail += 1;
+
+                for (_n, value, rows) in outlying {
+                    for row in rows.iter() {
+                        include_locators!(
+                            config,
+                            status,
+                            variable.name,
+                            variable.index,
+                            *row,
+                            value.value,
+                            format!(
+                                "outside {:.2} to {:.2} ({}, threshold {})",
+                                low, high, setting.setting.method, setting.setting.threshold
+                            )
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn string_value_odd_characters(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.data_integrity.string_value_odd_characters {
+        use check::CheckName::StringValueOddCharacters;
+        include_check!(
+            report.summary,
+            StringValueOddCharacters,
+            format!("{} {:?}", setting.desc, &setting.setting).as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&StringValueOddCharacters) {
+            for variable in (*context).variables.iter() {
+                if let Some(values) = (*context).frequency_table.get(&variable) {
+                    for (value, rows) in values.iter().filter(|(v, _)| match v.value {
+                        AnyValue::Str(_) => true,
+                        _ => false,
+                    }) {
+                        if let Some(found) = setting.setting.find(&format!("{}", &value.value)) {
+                            status.fail += 1;
+
+                            for row in rows {
+                                include_locators!(
+                                    config,
+                                    status,
+                                    value.variable.name,
+                                    value.variable.index,
+                                    *row,
+                                    value.value,
+                                    found
+                                );
+                            }
+                        } else {
+                            status.pass += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Report variables with a percentage of missing values over a specified
+/// threhold. The missing value types counted are configurable and the
+/// percentage of each type is recorded in the report for every variable.
+fn system_missing_over_threshold(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.data_integrity.system_missing_value_threshold {
+        let types = config.missing_value_types();
+
+        use check::CheckName::SystemMissingOverThreshold;
+        include_check!(
+            report.summary,
+            SystemMissingOverThreshold,
+            format!(
+                "{} (Threshold: {}%, counting {:?} missing)",
+                setting.desc, setting.setting, types
+            )
+            .as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&SystemMissingOverThreshold) {
+            for (variable, map) in &context.frequency_table {
+                let sum = map.iter().fold(0, |mut sum, (_, rows)| {
+                    sum += rows.len() as i32;
+                    sum
+                });
+
+                assert_eq!(
+                    report.metadata.raw_case_count, sum,
+                    "case_count {} does not align with sum {} for variable {}",
+                    report.metadata.raw_case_count, sum, variable.name
+                );
+
+                let percentage = |missing_type: MissingType| {
+                    let count = map
+                        .iter()
+                        .filter(|(value, _)| missing_type.matches(&value.missing))
+                        .fold(0, |count, (_, rows)| count + rows.len());
+
+                    (count as f32 * 100.0) / sum as f32
+                };
+
+                let percentages = MissingPercentages {
+                    system: percentage(MissingType::System),
+                    defined: percentage(MissingType::Defined),
+                    tagged: percentage(MissingType::Tagged),
+                };
+
+                // compare the included types with config threhold
+                // and increment fail
+                let missing = types
+                    .iter()
+                    .map(|missing_type| match missing_type {
+                        MissingType::System => percentages.system,
+                        MissingType::Defined => percentages.defined,
+                        MissingType::Tagged => percentages.tagged,
+                    })
+                    .sum::<f32>();
+
+                if missing > setting.setting as f32 {
+                    status.fail += 1;
+
+                    include_locators!(
+                        config,
+                        status,
+                        variable.name,
+                        variable.index,
+                        -1,
+                        format!("{:.1}%", missing),
+                        format!(
+                            "system {:.1}%, defined {:.1}%, tagged {:.1}%",
+                            percentages.system, percentages.defined, percentages.tagged
+                        )
+                    );
+                }
+
+                report.missing.insert(variable.name.clone(), percentages);
+            }
+
+            status.pass = report.metadata.variable_count - status.fail;
+        }
+    }
+}
+
+/// Report variables where every case has the same value, is system missing
+/// or is user-missing, unless exempted by name or pattern
+fn constant_variables(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.data_integrity.constant_variables {
+        use check::CheckName::ConstantVariables;
+        include_check!(
+            report.summary,
+            ConstantVariables,
+            format!("{} (Exempt variables {:?})", setting.desc, setting.setting).as_str(),
+            Category::DataIntegrity
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&ConstantVariables) {
+            let variables = context.variables.iter().filter(|v| {
+                !setting
+                    .setting
+                    .iter()
+                    .any(|pattern| glob_match(pattern, &v.name))
+            });
+
+            for variable in variables {
+                let map = match context.frequency_table.get(variable) {
+                    Some(map) if !map.is_empty() => map,
+                    _ => continue,
+                };
+
+                let kind = if map.keys().all(|v| v.missing == Missing::SYSTEM_MISSING) {
+                    "all system missing"
+                } else if map.keys().all(|v| {
+                    v.missing != Missing::NOT_MISSING && v.missing != Missing::SYSTEM_MISSING
+                }) {
+                    "all user-missing"
+                } else if map.keys().all(|v| v.missing != Missing::NOT_MISSING) {
+                    "all missing"
+                } else if map.len() == 1 {
+                    "constant"
+                } else {
+                    status.pass += 1;
+                    continue;
+                };
+
+                status.fail += 1;
+
+                let mut values = map.keys().cloned().collect::<Vec<Value>>();
+                values.sort_by_key(|v| format!("{}", v.value));
+
+                include_locators!(
+                    config,
+                    status,
+                    variable.name,
+                    variable.index,
+                    -1,
+                    join_values(&values),
+                    kind
+                );
+            }
+        }
+    }
+}
+
+/// Flags values that match a regex pattern
+fn regex_patterns(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.disclosure_risk.regex_patterns {
+        use check::CheckName::ValueRegexPatterns;
+        include_check!(
+            report.summary,
+            ValueRegexPatterns,
+            &setting.desc,
+            Category::DisclosureRisk
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&ValueRegexPatterns) {
+            for variable in context.variables.iter() {
+                for (value, rows) in context.frequency_table.get(&variable).unwrap() {
+                    for pattern in &setting.setting {
+                        let re = Regex::new(&pattern).unwrap();
+
+                        if re.is_match(&format!("{}", value.value)) || re.is_match(&value.label) {
+                            status.fail += 1;
+
+                            for row in rows {
+                                include_locators!(
+                                    config,
+                                    status,
+                                    value.variable.name,
+                                    value.variable.index,
+                                    *row,
+                                    value.value
+                                );
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+
+            status.pass = report.metadata.variable_count - status.fail;
+        }
+    }
+}
+
+/// Count the number of variables with one or more unique values, locating
+/// every row that holds one of those values
+fn unique_values(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.disclosure_risk.unique_values {
+        use check::CheckName::VariablesWithUniqueValues;
+        include_check!(
+            report.summary,
+            VariablesWithUniqueValues,
+            &setting.desc,
+            Category::DisclosureRisk
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&VariablesWithUniqueValues) {
+            for (variable, map) in context.frequency_table.iter() {
+                let rare = map
+                    .iter()
+                    .filter(|(_value, rows)| rows.len() as i32 <= setting.setting)
+                    .collect::<Vec<_>>();
+
+                if !rare.is_empty() {
+                    status.fail += 1;
+
+                    // every row holding a value at or below the threshold
+                    for (value, rows) in rare {
+                        for row in rows {
+                            include_locators!(
+                                config,
+                                status,
+                                variable.name,
+                                variable.index,
+                                *row,
+                                value.value
+                            );
+                        }
+                    }
+                } else {
+                    status.pass += 1
+                }
+            }
+        }
+    }
+}
+
+/// Report equivalence classes, records sharing the same quasi-identifier
+/// values, that are smaller than k. The totals count equivalence classes,
+/// each class smaller than k is located on every row in it. A set of
+/// quasi-identifiers naming a variable not in the file counts as one
+/// failure, locating the missing variables.
+fn k_anonymity(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.disclosure_risk.k_anonymity {
+        use check::CheckName::KAnonymity;
+        include_check!(
+            report.summary,
+            KAnonymity,
+            format!(
+                "{} (k = {}, quasi-identifiers {:?})",
+                setting.desc, setting.setting.k, setting.setting.quasi_identifiers
+            )
+            .as_str(),
+            Category::DisclosureRisk
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&KAnonymity) {
+            let k = setting.setting.k;
+
+            for quasi_identifiers in setting.setting.quasi_identifiers.iter() {
+                let key_name = quasi_identifiers.join(" + ");
+
+                let unknown = unknown_variables(&context.variables, quasi_identifiers);
+                if !unknown.is_empty() {
+                    status.fail += 1;
+
+                    for name in unknown {
+                        include_locators!(
+                            config,
+                            status,
+                            name,
+                            -1,
+                            -1,
+                            note: format!("not found in the file, part of quasi-identifiers {}", key_name)
+                        );
+                    }
+                    continue;
+                }
+
+                if let Some(classes) = context.tuples.get(quasi_identifiers) {
+                    for (values, rows) in classes.iter() {
+                        if (rows.len() as i32) < k {
+                            status.fail += 1;
+
+                            for row in rows {
+                                include_locators!(
+                                    config,
+                                    status,
+                                    key_name,
+                                    -1,
+                                    *row,
+                                    join_values(values),
+                                    format!("class size {} (k = {})", rows.len(), k)
+                                );
+                            }
+                        } else {
+                            status.pass += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Report groups of records sharing the same quasi-identifier values that
+/// have fewer than l distinct values of a sensitive variable
+fn l_diversity(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.disclosure_risk.l_diversity {
+        use check::CheckName::LDiversity;
+        include_check!(
+            report.summary,
+            LDiversity,
+            format!(
+                "{} (l = {}, quasi-identifiers {:?}, sensitive variables {:?})",
+                setting.desc,
+                setting.setting.l,
+                setting.setting.quasi_identifiers,
+                setting.setting.sensitive_variables
+            )
+            .as_str(),
+            Category::DisclosureRisk
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&LDiversity) {
+            let l = setting.setting.l;
+
+            for (group, sensitive) in setting
+                .setting
+                .groups()
+                .iter()
+                .zip(setting.setting.sensitive_variables.iter())
+            {
+                let occurrences = match context.tuples.get(group) {
+                    Some(occurrences) => occurrences,
+                    None => continue,
+                };
+
+                let variable_index = context
+                    .variables
+                    .iter()
+                    .find(|v| &v.name == sensitive)
+                    .map(|v| v.index)
+                    .unwrap_or(-1);
+
+                // distinct sensitive values and rows for each quasi-identifier group
+                let mut classes: HashMap<&[Value], (HashSet<&Value>, Vec<i32>)> = HashMap::new();
+                for (values, rows) in occurrences.iter() {
+                    let (quasi, sensitive_value) = values.split_at(values.len() - 1);
+                    let class = classes
+                        .entry(quasi)
+                        .or_insert_with(|| (HashSet::new(), vec![]));
+
+                    class.0.insert(&sensitive_value[0]);
+                    class.1.extend(rows.iter().cloned());
+                }
+
+                for (quasi, (distinct, rows)) in classes.iter() {
+                    if (distinct.len() as i32) < l {
+                        status.fail += 1;
+
+                        for row in rows {
+                            include_locators!(
+                                config,
+                                status,
+                                sensitive,
+                                variable_index,
+                                *row,
+                                join_values(quasi),
+                                format!(
+                                    "{} distinct value(s) across {} records (l = {})",
+                                    distinct.len(),
+                                    rows.len(),
+                                    l
+                                )
+                            );
+                        }
+                    } else {
+                        status.pass += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Score records by their minimal sample uniques across subsets of the key
+/// variables. The scores of the riskiest records and each variable's
+/// contribution to the risk are recorded in the report and located
+fn special_uniques(context: &mut Context) {
+    let (config, report) = (&context.config, &mut context.report);
+
+    if let Some(ref setting) = config.disclosure_risk.special_uniques {
+        use check::CheckName::SpecialUniques;
+        include_check!(
+            report.summary,
+            SpecialUniques,
+            format!(
+                "{} (key variables {:?}, max subset size {})",
+                setting.desc, setting.setting.key_variables, setting.setting.max_subset_size
+            )
+            .as_str(),
+            Category::DisclosureRisk
+        );
+
+        if let Some(ref mut status) = report.summary.get_mut(&SpecialUniques) {
+            let key_variables = &setting.setting.key_variables;
+
+            if let Some(records) = context.tuples.get(key_variables) {
+                let risk = suda::special_uniques(records, setting.setting.max_subset_size as usize);
+                let total_records = records.values().map(|rows| rows.len()).sum::<usize>();
+
+                status.fail += risk.records.len() as i32;
+                status.pass += (total_records - risk.records.len()) as i32;
+
+                let key_name = key_variables.join(" + ");
+                for record in risk.records.iter().take(setting.setting.top as usize) {
+                    let msus = record
+                        .msus
+                        .iter()
+                        .map(|msu| {
+                            msu.iter()
+                                .map(|index| key_variables[*index].clone())
+                                .collect::<Vec<String>>()
+                        })
+                        .collect::<Vec<Vec<String>>>();
+
+                    include_locators!(
+                        config,
+                        status,
+                        key_name,
+                        -1,
+                        record.row,
+                        join_values(record.values),
+                        format!(
+                            "risk score {}, minimal sample uniques {}",
+                            record.score,
+                            msus.iter()
+                                .map(|names| format!("{{{}}}", names.join(", ")))
+                                .collect::<Vec<String>>()
+                                .join(" ")
+                        )
+                    );
+
+                    report.risk.records.push(RecordRisk {
+                        row: record.row,
+                        score: record.score,
+                        values: record.values.iter().map(|v| format!("{}", v.value)).collect(),
+                        msus: msus,
+                    });
+                }
+
+                let total_score = risk.records.iter().map(|r| r.score).sum::<f64>();
+                for (name, contribution) in key_variables.iter().zip(risk.contributions.iter()) {
+                    report.risk.contributions.insert(name.clone(), *contribution);
+
+                    if *contribution > 0.0 {
+                        let variable_index = context
+                            .variables
+                            .iter()
+                            .find(|v| &v.name == name)
+                            .map(|v| v.index)
+                            .unwrap_or(-1);
+
+                        include_locators!(
+                            config,
+                            status,
+                            name,
+                            variable_index,
+                            -1,
+                            note: format!(
+                                "part of minimal sample uniques scoring {:.1}% of the total risk",
+                                contribution / total_score * 100.0
+                            )
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn string_value_stopword(context: &mut Context) {
+    use check::CheckName::StringValueStopword;
+
+    let variables: Vec<Variable> = context.variables.iter()
+        .filter(|v| v.type_ == VariableType::Text)
+        .map(|v| v.clone())
+        .collect();
+
+    let mut words: HashMap<String, Locator> = HashMap::new();
+    for var in variables {
+        if let Some(occurrences) = context.frequency_table.get(&var) {
+            for (val, _occ) in occurrences.iter() {
+                let locator = Locator::new(var.name.clone(), var.index, val.row)
+                    .with_value(&val.value);
+
+                words.insert(val.value.to_string(), locator.clone());
+            }
+        }
+    }
+
+    //println!("words: {:#?}", &words);
+
+    dictionary(context, StringValueStopword, &words, stopword_predicate);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use check::Check;
+    use config::{Config, OddCharacters, Setting};
+    use readstat::context::{Occurrences, TupleOccurrences};
+    use model::anyvalue::AnyValue;
+    use model::variable::{Alignment, Measure, Variable};
+    use report::Report;
+
+    use std::collections::HashMap;
+    // use report::anyvalue::AnyValue;
+
+    fn setup() -> Context {
+        let mut freq_table: HashMap<Variable, Occurrences> = HashMap::new();
+
+        {
+            let mut temp: Occurrences = HashMap::new();
+
+            let mut qux = Value::from("qux");
+            qux.label = String::from("this is fine");
+
+            let mut bar = Value::from("bar#");
+            bar.label = String::from("this@ is far too long to pss the test");
+
+            temp.insert(bar, vec![1, 2, 3]);
+            temp.insert(Value::from("!baz"), vec![4, 5, 6]);
+            temp.insert(qux, vec![7, 8, 9, 10]);
+
+            let variable = Variable {
+                index: 0,
                 name: "first".to_string(),
                 label: "first fine label".to_string(),
                 type_: VariableType::Text,
                 value_format: String::new(),
                 value_labels: "labels1".to_string(),
+                missing_ranges: vec![],
+                measure: Measure::Unknown,
+                alignment: Alignment::Unknown,
+                display_width: 0,
+                storage_width: 0,
+                decimals: None,
+            };
+
+            freq_table.insert(variable, temp.clone());
+        }
+
+        {
+            let mut temp: Occurrences = HashMap::new();
+            let mut missing_value: Value = Value::from("");
+            missing_value.missing = Missing::SYSTEM_MISSING;
+
+            temp.insert(Value::from("g@regs"), vec![1, 2]);
+            temp.insert(missing_value, (3..=10).collect());
+
+            let variable = Variable {
+                index: 1,
+                name: "second".to_string(),
+                label: "second fine label".to_string(),
+                type_: VariableType::Text,
+                value_format: String::new(),
+                value_labels: String::new(),
+                missing_ranges: vec![],
+                measure: Measure::Unknown,
+                alignment: Alignment::Unknown,
+                display_width: 0,
+                storage_width: 0,
+                decimals: None,
+            };
+
+            freq_table.insert(variable, temp);
+        }
+
+        {
+            let mut temp: Occurrences = HashMap::new();
+            let variable = Variable {
+                index: 2,
+                name: "badid".to_string(),
+                label: "this is nt ok".to_string(),
+                type_: VariableType::Numeric,
+                value_format: String::new(),
+                value_labels: String::new(),
+                missing_ranges: vec![],
+                measure: Measure::Unknown,
+                alignment: Alignment::Unknown,
+                display_width: 0,
+                storage_width: 0,
+                decimals: None,
+            };
+
+            for i in 1i32..=10 {
+                if i == 4 {
+                    continue;
+                }
+                // value 1 also takes the place of value 4
+                let rows = if i == 1 { vec![1, 4] } else { vec![i] };
+
+                temp.insert(
+                    Value {
+                        variable: variable.clone(),
+                        row: i,
+                        value: AnyValue::from(i),
+                        label: String::new(),
+                        missing: Missing::NOT_MISSING,
+                    },
+                    rows,
+                );
+            }
+
+            freq_table.insert(variable, temp);
+        }
+
+        {
+            let mut temp: Occurrences = HashMap::new();
+            let variable = Variable {
+                index: 3,
+                name: "id".to_string(),
+                label: "this is nt ok either".to_string(),
+                type_: VariableType::Numeric,
+                value_format: String::new(),
+                value_labels: String::new(),
+                missing_ranges: vec![],
+                measure: Measure::Unknown,
+                alignment: Alignment::Unknown,
+                display_width: 0,
+                storage_width: 0,
+                decimals: None,
             };
 
-            freq_table.insert(variable, temp.clone());
-        }
+            for i in 1i32..=10 {
+                temp.insert(
+                    Value {
+                        variable: variable.clone(),
+                        row: i,
+                        value: AnyValue::from(i),
+                        label: String::new(),
+                        missing: Missing::NOT_MISSING,
+                    },
+                    vec![i],
+                );
+            }
+
+            freq_table.insert(variable, temp);
+        }
+
+        let mut value_labels: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+        {
+            let mut temp: HashMap<String, String> = HashMap::new();
+
+            temp.insert("qux".to_string(),
+                "this is fine".to_string());
+            temp.insert("bar#".to_string(),
+                "this@ is far too long to pss the test".to_string());
+
+            value_labels.insert("labels1".to_string(), temp);
+        }
+
+        let mut report = Report::new();
+        report.metadata.variable_count = 4;
+        report.metadata.raw_case_count = 10;
+
+        let variables = freq_table.keys().map(|v| v.clone()).collect();
+
+        Context {
+            config: Config::default(),
+            report: report,
+            checks: Check {
+                variable: vec![],
+                value: vec![],
+                row: vec![],
+                post: vec![],
+            },
+            pb: None,
+            variables: variables,
+            value_labels: value_labels,
+            frequency_table: freq_table,
+            row: vec![],
+            tuples: HashMap::new(),
+            records: HashMap::new(),
+            record_variables: None,
+        }
+    }
+
+    #[test]
+    fn test_bad_filename() {
+        let mut context = setup();
+
+        use check::CheckName::BadFileName;
+
+        assert!(context.report.summary.get(&BadFileName).is_none());
+
+        context.config.basic_file_checks.bad_filename = Some(Setting {
+            setting: "^([a-zA-Z0-9]+)\\.([a-zA-Z0-9]+)$".to_string(),
+            desc: "filename must match pattern".to_string(),
+        });
+
+        context.report.metadata.file_name = "goodfilename.dta".to_string();
+        bad_filename(&mut context);
+        assert_setting!(context.report.summary.get(&BadFileName), 1, 0);
+
+        context.report.metadata.file_name = "bad& filename.foo".to_string();
+        bad_filename(&mut context);
+        assert_setting!(context.report.summary.get(&BadFileName), 0, 1);
+    }
+
+    #[test]
+    fn test_primary_variable() {
+        let mut context = setup();
+        assert!(context.report.metadata.case_count.is_none());
+
+        context.config.metadata.primary_variable = Some(Setting {
+            setting: String::from("first"),
+            desc: String::from("primary variable"),
+        });
+
+        primary_variable(&mut context);
+        if let Some(case_count) = context.report.metadata.case_count {
+            assert_eq!(case_count, 3);
+        } else {
+            assert!(
+                false,
+                concat!(
+                    "report.metadata.case_count should ",
+                    "be Some(i32) but is None"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_duplicate_values() {
+        let mut context = setup();
+
+        use check::CheckName::DuplicateValues;
+
+        assert!(context.report.summary.get_mut(&DuplicateValues).is_none());
+
+        context.config.data_integrity.duplicate_values = Some(Setting {
+            setting: vec!["id", "badid"].iter().map(|s| s.to_string()).collect(),
+            desc: "description from config".to_string(),
+        });
+
+        context.config.metadata_only = Some(false);
+
+        duplicate_values(&mut context);
+        assert_setting!(context.report.summary.get(&DuplicateValues), 1, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&DuplicateValues)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index, l.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                ("badid".to_string(), 1, Some("1".to_string())),
+                ("badid".to_string(), 4, Some("1".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_compound_keys() {
+        let mut context = setup();
+
+        use check::CheckName::DuplicateCompoundKeys;
+
+        assert!(context.report.summary.get(&DuplicateCompoundKeys).is_none());
+
+        let household = vec!["household".to_string(), "person".to_string()];
+        let wave = vec!["id".to_string(), "wave".to_string()];
+        let typo = vec!["HouseholdID".to_string(), "person".to_string()];
+
+        for name in vec!["household", "person", "wave"] {
+            context.variables.push(Variable::from(name));
+        }
+
+        // keys are tracked before the variables in the file are known
+        context.tuples.insert(typo.clone(), HashMap::new());
+
+        {
+            let mut occurrences: TupleOccurrences = HashMap::new();
+            occurrences.insert(vec![Value::from("1"), Value::from("1")], vec![1, 3]);
+            occurrences.insert(vec![Value::from("1"), Value::from("2")], vec![2]);
+            context.tuples.insert(household.clone(), occurrences);
+        }
+
+        {
+            let mut occurrences: TupleOccurrences = HashMap::new();
+            occurrences.insert(vec![Value::from("1"), Value::from("1")], vec![1]);
+            occurrences.insert(vec![Value::from("1"), Value::from("2")], vec![2]);
+            context.tuples.insert(wave.clone(), occurrences);
+        }
+
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.duplicate_compound_keys = Some(Setting {
+            setting: vec![household, wave, typo],
+            desc: "description from config".to_string(),
+        });
+
+        // the key with a variable not in the file fails
+        duplicate_compound_keys(&mut context);
+        assert_setting!(context.report.summary.get(&DuplicateCompoundKeys), 1, 2);
+
+        let locators = context
+            .report
+            .summary
+            .get(&DuplicateCompoundKeys)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index, l.value.clone().or(l.note.clone())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                (
+                    "HouseholdID".to_string(),
+                    -1,
+                    Some("not found in the file, part of key HouseholdID + person".to_string())
+                ),
+                ("household + person".to_string(), 1, Some("1, 1".to_string())),
+                ("household + person".to_string(), 3, Some("1, 1".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_system_missing_over_threshold() {
+        let mut context = setup();
+
+        use check::CheckName::SystemMissingOverThreshold;
+
+        assert!(context
+            .report
+            .summary
+            .get(&SystemMissingOverThreshold)
+            .is_none());
+
+        context.config.data_integrity.system_missing_value_threshold = Some(Setting {
+            setting: 25,
+            desc: String::from("sysmiss values over a threshold"),
+        });
+
+        system_missing_over_threshold(&mut context);
+        assert_setting!(
+            context.report.summary.get(&SystemMissingOverThreshold),
+            3,
+            1
+        );
+        assert_eq!(
+            context.report.missing.get("second"),
+            Some(&MissingPercentages {
+                system: 80.0,
+                defined: 0.0,
+                tagged: 0.0,
+            })
+        );
+
+        // user-missing values count towards the threshold unless excluded
+        let mut temp: Occurrences = HashMap::new();
+        let mut refused = Value::from("-9");
+        refused.missing = Missing::DEFINED_MISSING;
+        temp.insert(refused, (1..=3).collect());
+        temp.insert(Value::from("1"), (4..=10).collect());
+        context.frequency_table.insert(Variable::from("refused"), temp);
+
+        context.report = Report::new();
+        context.report.metadata.raw_case_count = 10;
+        context.report.metadata.variable_count = 5;
+        system_missing_over_threshold(&mut context);
+        assert_setting!(
+            context.report.summary.get(&SystemMissingOverThreshold),
+            3,
+            2
+        );
+
+        context.report = Report::new();
+        context.report.metadata.raw_case_count = 10;
+        context.report.metadata.variable_count = 5;
+        context.config.data_integrity.missing_value_types = Some(vec![MissingType::System]);
+        system_missing_over_threshold(&mut context);
+        assert_setting!(
+            context.report.summary.get(&SystemMissingOverThreshold),
+            4,
+            1
+        );
+        assert_eq!(context.report.missing["refused"].defined, 30.0);
+
+        // system and tagged missing values both display as NaN, each kind is
+        // counted separately
+        let mut variable = Variable::from("tagged");
+        variable.index = 5;
+        for row in 1..=10 {
+            let mut value = Value::from("1");
+            value.variable = variable.clone();
+            value.row = row;
+
+            if row <= 4 {
+                value.value = AnyValue::Double(Box::new(::std::f64::NAN));
+                value.missing = Missing::SYSTEM_MISSING;
+            } else if row <= 7 {
+                value.value = AnyValue::Double(Box::new(::std::f64::NAN));
+                value.missing = Missing::TAGGED_MISSING('a');
+            }
+
+            context.add_occurrence(&value);
+        }
+
+        context.report = Report::new();
+        context.report.metadata.raw_case_count = 10;
+        context.report.metadata.variable_count = 6;
+        context.config.data_integrity.missing_value_types = None;
+        system_missing_over_threshold(&mut context);
+        assert_eq!(
+            context.report.missing.get("tagged"),
+            Some(&MissingPercentages {
+                system: 40.0,
+                defined: 0.0,
+                tagged: 30.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_unique_values() {
+        let mut context = setup();
+
+        use check::CheckName::VariablesWithUniqueValues;
+
+        assert!(context
+            .report
+            .summary
+            .get(&VariablesWithUniqueValues)
+            .is_none());
+
+        context.config.disclosure_risk.unique_values = Some(Setting {
+            setting: 2,
+            desc: String::from("outliers as defined by the threshold"),
+        });
+
+        context.config.metadata_only = Some(false);
+
+        unique_values(&mut context);
+        assert_setting!(context.report.summary.get(&VariablesWithUniqueValues), 1, 3);
+
+        let status = context.report.summary.get(&VariablesWithUniqueValues).unwrap();
+        let rows = status
+            .into_iter()
+            .filter(|l| l.variable_name == "second")
+            .map(|l| l.value_index)
+            .collect::<Vec<i32>>();
+        assert_eq!(rows, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_k_anonymity() {
+        let mut context = setup();
+
+        use check::CheckName::KAnonymity;
+        use config::KAnonymity as KAnonymitySetting;
+
+        assert!(context.report.summary.get(&KAnonymity).is_none());
+
+        let quasi_identifiers = vec!["age".to_string(), "sex".to_string()];
+        let typo = vec!["age".to_string(), "gender".to_string()];
+
+        for name in vec!["age", "sex"] {
+            context.variables.push(Variable::from(name));
+        }
+
+        // sets are tracked before the variables in the file are known
+        context.tuples.insert(typo.clone(), HashMap::new());
+
+        let mut classes: TupleOccurrences = HashMap::new();
+        classes.insert(vec![Value::from("35"), Value::from("F")], vec![1]);
+        classes.insert(vec![Value::from("35"), Value::from("M")], vec![2, 3]);
+        classes.insert(vec![Value::from("40"), Value::from("F")], vec![4, 5, 6]);
+        context.tuples.insert(quasi_identifiers.clone(), classes);
+
+        context.config.metadata_only = Some(false);
+        context.config.disclosure_risk.k_anonymity = Some(Setting {
+            setting: KAnonymitySetting {
+                quasi_identifiers: vec![quasi_identifiers, typo],
+                k: 3,
+            },
+            desc: "description from config".to_string(),
+        });
+
+        // totals count classes, the set with a variable not in the file fails
+        k_anonymity(&mut context);
+        assert_setting!(context.report.summary.get(&KAnonymity), 1, 3);
+
+        let locators = context
+            .report
+            .summary
+            .get(&KAnonymity)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                ("gender".to_string(), -1),
+                ("age + sex".to_string(), 1),
+                ("age + sex".to_string(), 2),
+                ("age + sex".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_l_diversity() {
+        let mut context = setup();
+
+        use check::CheckName::LDiversity;
+        use config::LDiversity as LDiversitySetting;
+
+        assert!(context.report.summary.get(&LDiversity).is_none());
+
+        let mut groups: TupleOccurrences = HashMap::new();
+        groups.insert(vec![Value::from("35"), Value::from("flu")], vec![1, 2]);
+        groups.insert(vec![Value::from("40"), Value::from("flu")], vec![3]);
+        groups.insert(vec![Value::from("40"), Value::from("cold")], vec![4]);
+        context
+            .tuples
+            .insert(vec!["age".to_string(), "condition".to_string()], groups);
+
+        context.config.metadata_only = Some(false);
+        context.config.disclosure_risk.l_diversity = Some(Setting {
+            setting: LDiversitySetting {
+                quasi_identifiers: vec!["age".to_string()],
+                sensitive_variables: vec!["condition".to_string()],
+                l: 2,
+            },
+            desc: "description from config".to_string(),
+        });
+
+        l_diversity(&mut context);
+        assert_setting!(context.report.summary.get(&LDiversity), 1, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&LDiversity)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.value_index, l.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![(1, Some("35".to_string())), (2, Some("35".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_special_uniques() {
+        let mut context = setup();
+
+        use check::CheckName::SpecialUniques;
+        use config::SpecialUniques as SpecialUniquesSetting;
+
+        assert!(context.report.summary.get(&SpecialUniques).is_none());
+
+        let key_variables = vec!["age".to_string(), "sex".to_string(), "region".to_string()];
+
+        let mut records: TupleOccurrences = HashMap::new();
+        for (values, rows) in vec![
+            (vec!["1", "x", "p"], vec![1]),
+            (vec!["1", "y", "p"], vec![2]),
+            (vec!["2", "x", "p"], vec![3, 4]),
+            (vec!["3", "x", "q"], vec![5]),
+        ] {
+            records.insert(values.into_iter().map(Value::from).collect(), rows);
+        }
+        context.tuples.insert(key_variables.clone(), records);
+
+        context.config.metadata_only = Some(false);
+        context.config.disclosure_risk.special_uniques = Some(Setting {
+            setting: SpecialUniquesSetting {
+                key_variables: key_variables,
+                max_subset_size: 2,
+                top: 2,
+            },
+            desc: "description from config".to_string(),
+        });
+
+        special_uniques(&mut context);
+        assert_setting!(context.report.summary.get(&SpecialUniques), 2, 3);
+
+        // the contributing variables, then the two riskiest records
+        let locators = context
+            .report
+            .summary
+            .get(&SpecialUniques)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index, l.value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                ("region".to_string(), -1, None),
+                ("age".to_string(), -1, None),
+                ("sex".to_string(), -1, None),
+                ("age + sex + region".to_string(), 2, Some("1, y, p".to_string())),
+                ("age + sex + region".to_string(), 5, Some("3, x, q".to_string())),
+            ]
+        );
+
+        // the scores are recorded in the report, riskiest first
+        let names = |names: Vec<&str>| names.into_iter().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            context.report.risk.records,
+            vec![
+                RecordRisk {
+                    row: 5,
+                    score: 4.0,
+                    values: names(vec!["3", "x", "q"]),
+                    msus: vec![names(vec!["age"]), names(vec!["region"])],
+                },
+                RecordRisk {
+                    row: 2,
+                    score: 2.0,
+                    values: names(vec!["1", "y", "p"]),
+                    msus: vec![names(vec!["sex"])],
+                },
+            ]
+        );
+        assert_eq!(
+            context.report.risk.contributions.iter().collect::<Vec<_>>(),
+            vec![
+                (&"age".to_string(), &3.0),
+                (&"region".to_string(), &2.0),
+                (&"sex".to_string(), &3.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value_label_max_length() {
+        let mut context = setup();
+
+        use check::CheckName::ValueLabelMaxLength;
+
+        assert!(context.report.summary.get(&ValueLabelMaxLength).is_none());
+
+        context.config.metadata.value_label_max_length = Some(Setting {
+            setting: 20,
+            desc: String::from("value labels cannot be too long"),
+        });
+
+        value_label_max_length(&mut context);
+        assert_setting!(context.report.summary.get(&ValueLabelMaxLength), 3, 1);
+    }
 
-        {
-            let mut temp: HashMap<Value, i32> = HashMap::new();
-            let mut missing_value: Value = Value::from("");
-            missing_value.missing = Missing::SYSTEM_MISSING;
+    #[test]
+    fn test_duplicate_records() {
+        let mut context = setup();
 
-            temp.insert(Value::from("g@regs"), 2);
-            temp.insert(missing_value, 8);
+        use check::CheckName::DuplicateRecords;
 
-            let variable = Variable {
-                index: 1,
-                name: "second".to_string(),
-                label: "second fine label".to_string(),
-                type_: VariableType::Text,
-                value_format: String::new(),
-                value_labels: String::new(),
-            };
+        assert!(context.report.summary.get(&DuplicateRecords).is_none());
 
-            freq_table.insert(variable, temp);
+        for (record, rows) in vec![("a", vec![1]), ("b", vec![2, 5, 9]), ("c", vec![3]), ("d", vec![4, 8])] {
+            context.records.insert(vec![record.to_string(), "1".to_string()], rows);
         }
 
-        {
-            let mut temp: HashMap<Value, i32> = HashMap::new();
-            let variable = Variable {
-                index: 2,
-                name: "badid".to_string(),
-                label: "this is nt ok".to_string(),
-                type_: VariableType::Numeric,
-                value_format: String::new(),
-                value_labels: String::new(),
-            };
+        // the excluded id isn't compared
+        context.variables.sort_by_key(|v| v.index);
+        context.record_variables = Some(vec![true, true, true, false]);
 
-            for i in 1i32..=10 {
-                if i == 4 {
-                    continue;
-                }
-                let quant = if i == 1 { 2 } else { 1 };
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.duplicate_records = Some(Setting {
+            setting: vec!["id".to_string()],
+            desc: String::from("records should not be repeated"),
+        });
 
-                temp.insert(
-                    Value {
-                        variable: variable.clone(),
-                        row: i,
-                        value: AnyValue::from(i),
-                        label: String::new(),
-                        missing: Missing::NOT_MISSING,
-                    },
-                    quant,
-                );
-            }
+        // both totals count rows
+        duplicate_records(&mut context);
+        assert_setting!(context.report.summary.get(&DuplicateRecords), 2, 5);
 
-            freq_table.insert(variable, temp);
+        let locators = context
+            .report
+            .summary
+            .get(&DuplicateRecords)
+            .unwrap()
+            .into_iter()
+            .map(|l| {
+                (
+                    l.variable_name.clone(),
+                    l.value_index,
+                    l.value.clone().unwrap(),
+                    l.note.clone().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let compared = "first + second + badid".to_string();
+        assert_eq!(
+            locators,
+            vec![
+                (compared.clone(), 2, "b, 1".to_string(), "group 1, identical records on rows 2, 5, 9".to_string()),
+                (compared.clone(), 4, "d, 1".to_string(), "group 2, identical records on rows 4, 8".to_string()),
+                (compared.clone(), 5, "b, 1".to_string(), "group 1, identical records on rows 2, 5, 9".to_string()),
+                (compared.clone(), 8, "d, 1".to_string(), "group 2, identical records on rows 4, 8".to_string()),
+                (compared.clone(), 9, "b, 1".to_string(), "group 1, identical records on rows 2, 5, 9".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constant_variables() {
+        let mut context = setup();
+
+        use check::CheckName::ConstantVariables;
+
+        let mut variables = vec![];
+        for (name, missing) in vec![
+            ("wave", Missing::NOT_MISSING),
+            ("unused", Missing::SYSTEM_MISSING),
+            ("refused", Missing::DEFINED_MISSING),
+            ("year", Missing::NOT_MISSING),
+        ] {
+            let variable = Variable::from(name);
+            let mut value = Value::from(if missing == Missing::SYSTEM_MISSING { "" } else { "1" });
+            value.missing = missing;
+
+            let mut temp: Occurrences = HashMap::new();
+            temp.insert(value, (1..=10).collect());
+
+            context.frequency_table.insert(variable.clone(), temp);
+            variables.push(variable);
         }
 
+        // a mix of system and user-missing values
         {
-            let mut temp: HashMap<Value, i32> = HashMap::new();
-            let variable = Variable {
-                index: 3,
-                name: "id".to_string(),
-                label: "this is nt ok either".to_string(),
-                type_: VariableType::Numeric,
-                value_format: String::new(),
-                value_labels: String::new(),
-            };
+            let variable = Variable::from("skipped");
+            let mut system = Value::from("");
+            system.missing = Missing::SYSTEM_MISSING;
+            let mut defined = Value::from("-9");
+            defined.missing = Missing::DEFINED_MISSING;
+
+            let mut temp: Occurrences = HashMap::new();
+            temp.insert(system, (1..=5).collect());
+            temp.insert(defined, (6..=10).collect());
+
+            context.frequency_table.insert(variable.clone(), temp);
+            variables.push(variable);
+        }
+        context.variables.extend(variables);
 
-            for i in 1i32..=10 {
-                temp.insert(
-                    Value {
-                        variable: variable.clone(),
-                        row: i,
-                        value: AnyValue::from(i),
-                        label: String::new(),
-                        missing: Missing::NOT_MISSING,
-                    },
-                    1,
-                );
-            }
+        assert!(context.report.summary.get(&ConstantVariables).is_none());
 
-            freq_table.insert(variable, temp);
-        }
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.constant_variables = Some(Setting {
+            setting: vec!["y*r".to_string()],
+            desc: String::from("variables should not be constant"),
+        });
 
-        let mut value_labels: HashMap<String, HashMap<String, String>> = HashMap::new();
+        constant_variables(&mut context);
+        assert_setting!(context.report.summary.get(&ConstantVariables), 4, 4);
 
-        {
-            let mut temp: HashMap<String, String> = HashMap::new();
+        let mut locators = context
+            .report
+            .summary
+            .get(&ConstantVariables)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        locators.sort();
+        assert_eq!(
+            locators,
+            vec![
+                ("refused".to_string(), "all user-missing".to_string()),
+                ("skipped".to_string(), "all missing".to_string()),
+                ("unused".to_string(), "all system missing".to_string()),
+                ("wave".to_string(), "constant".to_string()),
+            ]
+        );
+    }
 
-            temp.insert("qux".to_string(),
-                "this is fine".to_string());
-            temp.insert("bar#".to_string(),
-                "this@ is far too long to pss the test".to_string());
+    #[test]
+    fn test_numeric_outliers() {
+        use config::{OutlierMethod, Outliers};
+        use check::CheckName::NumericOutliers;
 
-            value_labels.insert("labels1".to_string(), temp);
-        }
+        let mut context = setup();
 
-        let mut report = Report::new();
-        report.metadata.variable_count = 4;
-        report.metadata.raw_case_count = 10;
+        let variable = Variable {
+            index: 4,
+            name: "income".to_string(),
+            label: "income".to_string(),
+            type_: VariableType::Numeric,
+            value_format: String::new(),
+            value_labels: String::new(),
+            missing_ranges: vec![],
+            measure: Measure::Unknown,
+            alignment: Alignment::Unknown,
+            display_width: 0,
+            storage_width: 0,
+            decimals: None,
+        };
+
+        let mut temp: Occurrences = HashMap::new();
+        for (row, income) in (1..=10).chain(vec![9999999]).enumerate() {
+            temp.insert(
+                Value {
+                    variable: variable.clone(),
+                    row: row as i32 + 1,
+                    value: AnyValue::from(income),
+                    label: String::new(),
+                    missing: Missing::NOT_MISSING,
+                },
+                vec![row as i32 + 1],
+            );
+        }
+        context.frequency_table.insert(variable.clone(), temp);
+        context.variables.push(variable);
 
-        let variables = freq_table.keys().map(|v| v.clone()).collect();
+        assert!(context.report.summary.get(&NumericOutliers).is_none());
 
-        Context {
-            config: Config::default(),
-            report: report,
-            checks: Check {
-                variable: vec![],
-                value: vec![],
-                post: vec![],
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.outliers = Some(Setting {
+            setting: Outliers {
+                method: OutlierMethod::Iqr,
+                threshold: 1.5,
+                variables: None,
             },
-            pb: None,
-            variables: variables,
-            value_labels: value_labels,
-            frequency_table: freq_table,
+            desc: String::from("numeric values should not be outliers"),
+        });
+
+        numeric_outliers(&mut context);
+        assert_setting!(context.report.summary.get(&NumericOutliers), 2, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&NumericOutliers)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index, l.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![(
+                "income".to_string(),
+                11,
+                Some("outside -4.00 to 16.00 (IQR, threshold 1.5)".to_string())
+            )]
+        );
+
+        // limited to variables matching a pattern
+        context.report = Report::new();
+        if let Some(ref mut setting) = context.config.data_integrity.outliers {
+            setting.setting.method = OutlierMethod::Mad;
+            setting.setting.threshold = 3.5;
+            setting.setting.variables = Some(vec!["inc*".to_string()]);
         }
+
+        numeric_outliers(&mut context);
+        assert_setting!(context.report.summary.get(&NumericOutliers), 0, 1);
     }
 
     #[test]
-    fn test_bad_filename() {
+    fn test_missing_code_consistency() {
+        use model::variable::MissingRange;
+        use check::CheckName::MissingCodeConsistency;
+
         let mut context = setup();
 
-        use check::CheckName::BadFileName;
+        for (index, (name, codes)) in vec![
+            ("q1", vec![-9, -8]),
+            ("q2", vec![-8, -9]),
+            ("q3", vec![-9]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut variable = Variable::from(name);
+            variable.index = 10 + index as i32;
+            variable.type_ = VariableType::Numeric;
+            variable.value_labels = "missing".to_string();
+            variable.missing_ranges = codes
+                .into_iter()
+                .map(|code| MissingRange {
+                    lo: AnyValue::from(code),
+                    hi: AnyValue::from(code),
+                })
+                .collect();
+            context.variables.push(variable);
+        }
 
-        assert!(context.report.summary.get(&BadFileName).is_none());
+        let mut labels = HashMap::new();
+        labels.insert("-9".to_string(), "Refused".to_string());
+        context.value_labels.insert("missing".to_string(), labels);
 
-        context.config.basic_file_checks.bad_filename = Some(Setting {
-            setting: "^([a-zA-Z0-9]+)\\.([a-zA-Z0-9]+)$".to_string(),
-            desc: "filename must match pattern".to_string(),
+        assert!(context.report.summary.get(&MissingCodeConsistency).is_none());
+
+        context.config.metadata_only = Some(false);
+        context.config.metadata.missing_value_codes = Some(Setting {
+            setting: vec![],
+            desc: String::from("variables should use the same missing codes"),
         });
 
-        context.report.metadata.file_name = "goodfilename.dta".to_string();
-        bad_filename(&mut context);
-        assert_setting!(context.report.summary.get(&BadFileName), 1, 0);
+        missing_code_consistency(&mut context);
+        assert_setting!(context.report.summary.get(&MissingCodeConsistency), 2, 1);
 
-        context.report.metadata.file_name = "bad& filename.foo".to_string();
-        bad_filename(&mut context);
-        assert_setting!(context.report.summary.get(&BadFileName), 0, 1);
+        let locators = context
+            .report
+            .summary
+            .get(&MissingCodeConsistency)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value.clone(), l.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![(
+                "q3".to_string(),
+                Some("-9 (Refused)".to_string()),
+                Some("differs from the codes of 2 variables, -9, -8".to_string())
+            )]
+        );
+
+        // compared with the standard codes instead
+        context.report = Report::new();
+        context.config.metadata.missing_value_codes = Some(Setting {
+            setting: vec![-9.0, -8.0, -1.0],
+            desc: String::from("variables should use the standard missing codes"),
+        });
+
+        missing_code_consistency(&mut context);
+        assert_setting!(context.report.summary.get(&MissingCodeConsistency), 0, 3);
+
+        // NaN codes are ignored rather than compared
+        context.report = Report::new();
+        context.variables[4].missing_ranges[0].lo = AnyValue::from("NaN");
+        context.config.metadata.missing_value_codes = Some(Setting {
+            setting: vec![-9.0, -8.0, ::std::f64::NAN],
+            desc: String::from("variables should use the standard missing codes"),
+        });
+
+        missing_code_consistency(&mut context);
+        assert_setting!(context.report.summary.get(&MissingCodeConsistency), 1, 2);
     }
 
     #[test]
-    fn test_primary_variable() {
+    fn test_measure_consistency() {
+        use config::MeasureConsistency;
+        use check::CheckName::MeasureConsistency as MeasureConsistencyCheck;
+
         let mut context = setup();
-        assert!(context.report.metadata.case_count.is_none());
 
-        context.config.metadata.primary_variable = Some(Setting {
-            setting: String::from("first"),
-            desc: String::from("primary variable"),
+        for variable in context.variables.iter_mut() {
+            variable.measure = match variable.name.as_str() {
+                "first" => Measure::Scale,
+                "second" => Measure::Ordinal,
+                _ => Measure::Nominal,
+            };
+        }
+
+        assert!(context.report.summary.get(&MeasureConsistencyCheck).is_none());
+
+        context.config.metadata_only = Some(false);
+        context.config.metadata.measure_consistency = Some(Setting {
+            setting: MeasureConsistency {
+                scale_labelled_percentage: 50,
+                nominal_max_distinct: 8,
+                ordinal_strings: true,
+            },
+            desc: String::from("measure should match the labels and values"),
         });
 
-        primary_variable(&mut context);
-        if let Some(case_count) = context.report.metadata.case_count {
-            assert_eq!(case_count, 3);
-        } else {
-            assert!(
-                false,
-                concat!(
-                    "report.metadata.case_count should ",
-                    "be Some(i32) but is None"
-                )
-            );
-        }
+        measure_consistency(&mut context);
+        assert_setting!(context.report.summary.get(&MeasureConsistencyCheck), 0, 4);
+
+        let mut notes = context
+            .report
+            .summary
+            .get(&MeasureConsistencyCheck)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        notes.sort();
+        assert_eq!(
+            notes,
+            vec![
+                ("badid".to_string(), "nominal with 9 distinct values".to_string()),
+                ("first".to_string(), "scale with 2 of 3 distinct values labelled".to_string()),
+                ("id".to_string(), "nominal with 10 distinct values".to_string()),
+                ("second".to_string(), "ordinal string".to_string()),
+            ]
+        );
     }
 
     #[test]
-    fn test_duplicate_values() {
+    fn test_value_label_invisible_characters() {
         let mut context = setup();
 
-        use check::CheckName::DuplicateValues;
+        use check::CheckName::InvisibleCharacters;
 
-        assert!(context.report.summary.get_mut(&DuplicateValues).is_none());
+        context
+            .value_labels
+            .get_mut("labels1")
+            .unwrap()
+            .insert("!baz".to_string(), "\u{FEFF}baz ".to_string());
 
-        context.config.data_integrity.duplicate_values = Some(Setting {
-            setting: vec!["id", "badid"].iter().map(|s| s.to_string()).collect(),
-            desc: "description from config".to_string(),
+        // a second variable sharing the label set
+        let mut shared = Variable::from("shared");
+        shared.index = 4;
+        shared.value_labels = "labels1".to_string();
+        context.variables.push(shared);
+
+        assert!(context.report.summary.get(&InvisibleCharacters).is_none());
+
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.invisible_characters = Some(Setting {
+            setting: true,
+            desc: String::from("labels should not contain invisible characters"),
         });
 
-        duplicate_values(&mut context);
-        assert_setting!(context.report.summary.get(&DuplicateValues), 1, 1);
+        value_label_invisible_characters(&mut context);
+        assert_setting!(context.report.summary.get(&InvisibleCharacters), 2, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&InvisibleCharacters)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                (
+                    "first".to_string(),
+                    "trailing whitespace, zero-width character in value label !baz".to_string()
+                ),
+                (
+                    "shared".to_string(),
+                    "trailing whitespace, zero-width character in value label !baz".to_string()
+                ),
+            ]
+        );
     }
 
     #[test]
-    fn test_system_missing_over_threshold() {
+    fn test_value_label_mojibake() {
         let mut context = setup();
 
-        use check::CheckName::SystemMissingOverThreshold;
+        use check::CheckName::Mojibake;
 
-        assert!(context
-            .report
-            .summary
-            .get(&SystemMissingOverThreshold)
-            .is_none());
+        context
+            .value_labels
+            .get_mut("labels1")
+            .unwrap()
+            .insert("!baz".to_string(), "cafÃ©".to_string());
 
-        context.config.data_integrity.system_missing_value_threshold = Some(Setting {
-            setting: 25,
-            desc: String::from("sysmiss values over a threshold"),
+        // a second variable sharing the label set
+        let mut shared = Variable::from("shared");
+        shared.index = 4;
+        shared.value_labels = "labels1".to_string();
+        context.variables.push(shared);
+
+        assert!(context.report.summary.get(&Mojibake).is_none());
+
+        context.config.metadata_only = Some(false);
+        context.config.data_integrity.mojibake = Some(Setting {
+            setting: true,
+            desc: String::from("labels should not contain mojibake"),
         });
 
-        system_missing_over_threshold(&mut context);
-        assert_setting!(
-            context.report.summary.get(&SystemMissingOverThreshold),
-            3,
-            1
+        value_label_mojibake(&mut context);
+        assert_setting!(context.report.summary.get(&Mojibake), 2, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&Mojibake)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                (
+                    "first".to_string(),
+                    "\"Ã©\" in value label !baz, file encoding not declared".to_string()
+                ),
+                (
+                    "shared".to_string(),
+                    "\"Ã©\" in value label !baz, file encoding not declared".to_string()
+                ),
+            ]
         );
     }
 
     #[test]
-    fn test_unique_values() {
+    fn test_values_without_labels() {
         let mut context = setup();
 
-        use check::CheckName::VariablesWithUniqueValues;
+        use check::CheckName::ValuesWithoutLabels;
 
-        assert!(context
-            .report
-            .summary
-            .get(&VariablesWithUniqueValues)
-            .is_none());
+        assert!(context.report.summary.get(&ValuesWithoutLabels).is_none());
 
-        context.config.disclosure_risk.unique_values = Some(Setting {
-            setting: 2,
-            desc: String::from("outliers as defined by the threshold"),
+        context.config.metadata_only = Some(false);
+        context.config.metadata.values_without_labels = Some(Setting {
+            setting: true,
+            desc: String::from("labelled variables should have labels for all values"),
         });
 
-        unique_values(&mut context);
-        assert_setting!(context.report.summary.get(&VariablesWithUniqueValues), 1, 3);
+        values_without_labels(&mut context);
+        assert_setting!(context.report.summary.get(&ValuesWithoutLabels), 2, 1);
+
+        let locators = context
+            .report
+            .summary
+            .get(&ValuesWithoutLabels)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value.clone(), l.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![(
+                "first".to_string(),
+                Some("!baz".to_string()),
+                Some("unlabelled, occurs 3 times".to_string())
+            )]
+        );
     }
 
     #[test]
-    fn test_value_label_max_length() {
+    fn test_unused_value_labels() {
         let mut context = setup();
 
-        use check::CheckName::ValueLabelMaxLength;
+        use check::CheckName::UnusedValueLabels;
 
-        assert!(context.report.summary.get(&ValueLabelMaxLength).is_none());
+        assert!(context.report.summary.get(&UnusedValueLabels).is_none());
 
-        context.config.metadata.value_label_max_length = Some(Setting {
-            setting: 20,
-            desc: String::from("value labels cannot be too long"),
+        if let Some(labels) = context.value_labels.get_mut("labels1") {
+            labels.insert("zzz".to_string(), "never used".to_string());
+        }
+
+        let mut orphan: HashMap<String, String> = HashMap::new();
+        orphan.insert("1".to_string(), "yes".to_string());
+        context.value_labels.insert("orphan".to_string(), orphan);
+
+        context.config.metadata_only = Some(false);
+        context.config.metadata.unused_value_labels = Some(Setting {
+            setting: true,
+            desc: String::from("value labels should be used"),
         });
 
-        value_label_max_length(&mut context);
-        assert_setting!(context.report.summary.get(&ValueLabelMaxLength), 3, 1);
+        unused_value_labels(&mut context);
+        assert_setting!(context.report.summary.get(&UnusedValueLabels), 2, 2);
+
+        let values = context
+            .report
+            .summary
+            .get(&UnusedValueLabels)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.value.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![Some("orphan".to_string()), Some("zzz".to_string())]
+        );
     }
 
     #[test]
@@ -735,16 +2737,54 @@ mod tests {
             .get(&ValueLabelOddCharacters)
             .is_none());
 
+        context.config.metadata_only = Some(false);
         context.config.metadata.value_label_odd_characters = Some(Setting {
-            setting: vec!["#", "@", "!"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
+            setting: OddCharacters::new(
+                vec!["#", "@", "!"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>(),
+            )
+            .unwrap(),
+            desc: String::from("value labels shouldn't contain some characters"),
+        });
+
+        value_label_odd_characters(&mut context);
+        assert_setting!(context.report.summary.get(&ValueLabelOddCharacters), 1, 1);
+
+        let notes = context
+            .report
+            .summary
+            .get(&ValueLabelOddCharacters)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.note.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(notes, vec!["\"@\""]);
+
+        // unicode classes and allow lists
+        context.report = Report::new();
+        context.config.metadata.value_label_odd_characters = Some(Setting {
+            setting: OddCharacters::new(vec![
+                "\\p{Lu}".to_string(),
+                "allow:[a-z ]".to_string(),
+            ])
+            .unwrap(),
             desc: String::from("value labels shouldn't contain some characters"),
         });
 
         value_label_odd_characters(&mut context);
         assert_setting!(context.report.summary.get(&ValueLabelOddCharacters), 1, 1);
+
+        let notes = context
+            .report
+            .summary
+            .get(&ValueLabelOddCharacters)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.note.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(notes, vec!["'@' is not allowed by allow:[a-z ]"]);
     }
 
     #[test]
@@ -760,12 +2800,18 @@ mod tests {
             .is_none());
 
         context.config.data_integrity.string_value_odd_characters = Some(Setting {
-            setting: vec!["#", "@", "!"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
+            setting: OddCharacters::new(
+                vec!["#", "@", "!"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>(),
+            )
+            .unwrap(),
             desc: String::from("description from config"),
-        })
+        });
+
+        string_value_odd_characters(&mut context);
+        assert_setting!(context.report.summary.get(&StringValueOddCharacters), 2, 3);
     }
 
     #[test]
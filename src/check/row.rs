@@ -0,0 +1,248 @@
+use check::RowCheckFn;
+use config::Config;
+use model::missing::Missing;
+use model::value::Value;
+use report::{Category, Locator, Report, Status};
+
+/// Register the checks, each is run with the values of a complete row once
+/// they have all been read
+pub fn register() -> Vec<RowCheckFn> {
+    vec![record_missing_over_threshold, consistency_rules]
+}
+
+// Row checks
+
+/// Report records where the percentage of system or user-missing values is
+/// over the threshold, e.g. respondents who broke off early
+fn record_missing_over_threshold(row: &[Value], config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.record_missing_threshold {
+        use check::CheckName::RecordMissingOverThreshold;
+        include_check!(
+            report.summary,
+            RecordMissingOverThreshold,
+            format!("{} (Threshold: {}%)", setting.desc, setting.setting).as_str(),
+            Category::DataIntegrity
+        );
+
+        let row_index = match row.first() {
+            Some(value) => value.row,
+            None => return,
+        };
+
+        if let Some(ref mut status) = report.summary.get_mut(&RecordMissingOverThreshold) {
+            let missing = row
+                .iter()
+                .filter(|value| value.missing != Missing::NOT_MISSING)
+                .count();
+            let percentage = (missing as f32 / row.len() as f32) * 100.0;
+
+            if percentage > setting.setting as f32 {
+                status.fail += 1;
+
+                include_locators!(
+                    config,
+                    status,
+                    String::new(),
+                    -1,
+                    row_index,
+                    format!("{:.1}%", percentage),
+                    format!("{} of {} values missing", missing, row.len())
+                );
+            } else {
+                status.pass += 1;
+            }
+        }
+    }
+}
+
+/// Evaluate each consistency rule against the values of the row, locating
+/// the rules it breaks. Rules which can't be decided because a value is
+/// missing are not broken, rules naming a variable not in the file are.
+/// The totals count every rule on every row.
+fn consistency_rules(row: &[Value], config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.consistency_rules {
+        use check::CheckName::ConsistencyRules;
+        include_check!(
+            report.summary,
+            ConsistencyRules,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        let row_index = match row.first() {
+            Some(value) => value.row,
+            None => return,
+        };
+
+        if let Some(ref mut status) = report.summary.get_mut(&ConsistencyRules) {
+            let lookup = |name: &str| row.iter().find(|value| value.variable.name == name);
+
+            for rule in setting.setting.iter() {
+                let unknown = rule
+                    .variables()
+                    .iter()
+                    .filter(|name| lookup(name).is_none())
+                    .map(|name| name.as_str())
+                    .collect::<Vec<&str>>();
+
+                if !unknown.is_empty() {
+                    status.fail += 1;
+                    include_locators!(
+                        config,
+                        status,
+                        rule.name,
+                        -1,
+                        -1,
+                        rule.rule,
+                        format!("unknown variables {}", unknown.join(", "))
+                    );
+                    continue;
+                }
+
+                if rule.expression().evaluate(&lookup) != Some(false) {
+                    status.pass += 1;
+                    continue;
+                }
+
+                status.fail += 1;
+
+                let assignments = rule
+                    .variables()
+                    .iter()
+                    .filter_map(|name| lookup(name))
+                    .map(|value| format!("{} = {}", value.variable.name, value.value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                include_locators!(
+                    config,
+                    status,
+                    rule.name,
+                    -1,
+                    row_index,
+                    assignments,
+                    format!("breaks {}", rule.rule)
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use config::{ConsistencyRule, Setting};
+    use model::variable::Variable;
+
+    fn record(row: i32, values: Vec<&str>) -> Vec<Value> {
+        values
+            .into_iter()
+            .map(|v| {
+                let mut value = Value::from(v);
+                value.row = row;
+                value.missing = match v {
+                    "" => Missing::SYSTEM_MISSING,
+                    "-9" => Missing::DEFINED_MISSING,
+                    _ => Missing::NOT_MISSING,
+                };
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_missing_over_threshold() {
+        use check::CheckName::RecordMissingOverThreshold;
+
+        let mut config = Config::default();
+        let mut report = Report::new();
+
+        config.metadata_only = Some(false);
+        config.data_integrity.record_missing_threshold = Some(Setting {
+            setting: 50,
+            desc: "records should be mostly complete".to_string(),
+        });
+
+        record_missing_over_threshold(&record(1, vec!["1", "2", "", "4"]), &config, &mut report);
+        record_missing_over_threshold(&record(2, vec!["1", "", "-9", "4"]), &config, &mut report);
+        record_missing_over_threshold(&record(3, vec!["1", "", "-9", ""]), &config, &mut report);
+
+        assert_setting!(report.summary.get(&RecordMissingOverThreshold), 2, 1);
+
+        let locators = report
+            .summary
+            .get(&RecordMissingOverThreshold)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.value_index, l.value.clone(), l.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![(
+                3,
+                Some("75.0%".to_string()),
+                Some("3 of 4 values missing".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_consistency_rules() {
+        use check::CheckName::ConsistencyRules;
+
+        let mut config = Config::default();
+        let mut report = Report::new();
+
+        config.metadata_only = Some(false);
+        config.data_integrity.consistency_rules = Some(Setting {
+            setting: vec![
+                ConsistencyRule::new("unemployed without hours", "if employed == 2 then missing(hours)").unwrap(),
+                ConsistencyRule::new("hours in a week", "hours <= 168").unwrap(),
+                ConsistencyRule::new("unknown", "age > 15").unwrap(),
+            ],
+            desc: "rows should satisfy the consistency rules".to_string(),
+        });
+
+        for (row, employed, hours) in vec![(1, "1", "40"), (2, "2", ""), (3, "2", "35")] {
+            let values = record(row, vec![employed, hours])
+                .into_iter()
+                .zip(vec!["employed", "hours"])
+                .map(|(mut value, name)| {
+                    value.variable = Variable::from(name);
+                    value
+                })
+                .collect::<Vec<Value>>();
+
+            consistency_rules(&values, &config, &mut report);
+        }
+
+        // each rule is counted on each row
+        assert_setting!(report.summary.get(&ConsistencyRules), 5, 4);
+
+        let locators = report
+            .summary
+            .get(&ConsistencyRules)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.variable_name.clone(), l.value_index, l.value.clone(), l.note.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![
+                (
+                    "unknown".to_string(),
+                    -1,
+                    Some("age > 15".to_string()),
+                    Some("unknown variables age".to_string())
+                ),
+                (
+                    "unemployed without hours".to_string(),
+                    3,
+                    Some("employed = 2, hours = 35".to_string()),
+                    Some("breaks if employed == 2 then missing(hours)".to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use model::value::Value;
+use readstat::context::TupleOccurrences;
+
+use std::collections::HashMap;
+
+/// Risk of a single record, a record is only at risk if it is a sample
+/// unique across all of the key variables
+#[derive(Debug)]
+pub struct RecordRisk<'a> {
+    pub values: &'a [Value],
+    pub row: i32,
+    pub score: f64,
+    /// Minimal sample uniques, as indices into the key variables
+    pub msus: Vec<Vec<usize>>,
+}
+
+#[derive(Debug)]
+pub struct Risk<'a> {
+    pub records: Vec<RecordRisk<'a>>,
+    /// Score contributed by each key variable, summed over the minimal
+    /// sample uniques it is part of
+    pub contributions: Vec<f64>,
+}
+
+/// Find the minimal sample uniques (MSUs) of each record across subsets of
+/// the key variables up to `max_subset_size`, and score the records in the
+/// manner of SUDA. A MSU of size k contributes the product of
+/// (key variable count - i) for i in k..max_subset_size.
+pub fn special_uniques(records: &TupleOccurrences, max_subset_size: usize) -> Risk {
+    let variable_count = match records.keys().next() {
+        Some(values) => values.len(),
+        None => 0,
+    };
+    let max_subset_size = max_subset_size.min(variable_count);
+
+    let mut risk = Risk {
+        records: vec![],
+        contributions: vec![0.0; variable_count],
+    };
+
+    // subsets in increasing size, so smaller MSUs are always found first
+    let subsets: Vec<Vec<usize>> = (1..=max_subset_size)
+        .flat_map(|size| combinations(variable_count, size))
+        .collect();
+
+    // frequency of every projection of the records onto each subset
+    let frequencies: Vec<HashMap<Vec<&Value>, usize>> = subsets
+        .iter()
+        .map(|subset| {
+            let mut frequency = HashMap::new();
+            for (values, rows) in records.iter() {
+                *frequency.entry(project(values, subset)).or_insert(0) += rows.len();
+            }
+            frequency
+        })
+        .collect();
+
+    for (values, rows) in records.iter().filter(|(_, rows)| rows.len() == 1) {
+        let mut msus: Vec<Vec<usize>> = vec![];
+
+        for (subset, frequency) in subsets.iter().zip(frequencies.iter()) {
+            let minimal = !msus
+                .iter()
+                .any(|msu| msu.iter().all(|index| subset.contains(index)));
+
+            if minimal && frequency.get(&project(values, subset)) == Some(&1) {
+                msus.push(subset.clone());
+            }
+        }
+
+        if msus.is_empty() {
+            continue;
+        }
+
+        let mut score = 0.0;
+        for msu in msus.iter() {
+            let msu_score = (msu.len()..max_subset_size)
+                .map(|i| (variable_count - i) as f64)
+                .product::<f64>();
+
+            for index in msu.iter() {
+                risk.contributions[*index] += msu_score;
+            }
+            score += msu_score;
+        }
+
+        risk.records.push(RecordRisk {
+            values: values,
+            row: rows[0],
+            score: score,
+            msus: msus,
+        });
+    }
+
+    // riskiest records first
+    risk.records.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap()
+            .then(a.row.cmp(&b.row))
+    });
+
+    risk
+}
+
+fn project<'a>(values: &'a [Value], subset: &Vec<usize>) -> Vec<&'a Value> {
+    subset.iter().map(|index| &values[*index]).collect()
+}
+
+/// All combinations of `size` indices drawn from 0..n
+fn combinations(n: usize, size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![vec![]];
+    }
+
+    let mut result = vec![];
+    for last in (size - 1)..n {
+        for mut combination in combinations(last, size - 1) {
+            combination.push(last);
+            result.push(combination);
+        }
+    }
+
+    result.sort();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(values: Vec<&str>) -> Vec<Value> {
+        values.into_iter().map(Value::from).collect()
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            combinations(3, 2),
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]]
+        );
+        assert_eq!(combinations(3, 3), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_special_uniques() {
+        let mut records: TupleOccurrences = HashMap::new();
+        records.insert(record(vec!["1", "x", "p"]), vec![1]);
+        records.insert(record(vec!["1", "y", "p"]), vec![2]);
+        records.insert(record(vec!["2", "x", "p"]), vec![3, 4]);
+        records.insert(record(vec!["3", "x", "q"]), vec![5]);
+
+        let risk = special_uniques(&records, 2);
+
+        let scores = risk
+            .records
+            .iter()
+            .map(|r| (r.row, r.score, r.msus.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scores,
+            vec![
+                (5, 4.0, vec![vec![0], vec![2]]),
+                (2, 2.0, vec![vec![1]]),
+                (1, 1.0, vec![vec![0, 1]]),
+            ]
+        );
+
+        assert_eq!(risk.contributions, vec![3.0, 3.0, 2.0]);
+    }
+}
@@ -1,15 +1,20 @@
+use check::{invisible_characters, mojibake, mojibake_note};
 use config::Config;
 use model::missing::Missing;
 use model::value::Value;
+use model::variable::VariableType;
 use report::{Category, Locator, Report, Status};
 
 use check::ValueCheckFn;
 
-use std::collections::HashSet;
-
 /// Register the checks with the context object
 pub fn register() -> Vec<ValueCheckFn> {
-    vec![value_defined_missing_no_label]
+    vec![
+        value_defined_missing_no_label,
+        string_value_invisible_characters,
+        string_value_mojibake,
+        value_out_of_range,
+    ]
 }
 
 // Value checks
@@ -34,7 +39,8 @@ fn value_defined_missing_no_label(value: &Value, config: &Config, report: &mut R
                     status,
                     value.variable.name,
                     value.variable.index,
-                    value.row
+                    value.row,
+                    value.value
                 );
             } else {
                 status.pass += 1;
@@ -43,13 +49,146 @@ fn value_defined_missing_no_label(value: &Value, config: &Config, report: &mut R
     }
 }
 
+/// Check string values for stray whitespace and invisible characters, each
+/// kind found is noted on the locator
+fn string_value_invisible_characters(value: &Value, config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.invisible_characters {
+        use check::CheckName::InvisibleCharacters;
+        include_check!(
+            report.summary,
+            InvisibleCharacters,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        if !setting.setting
+            || value.missing != Missing::NOT_MISSING
+            || value.variable.type_ != VariableType::Text
+        {
+            return;
+        }
+
+        if let Some(ref mut status) = report.summary.get_mut(&InvisibleCharacters) {
+            let text = format!("{}", value.value);
+            let kinds = invisible_characters(&text);
+
+            if kinds.is_empty() {
+                status.pass += 1;
+            } else {
+                status.fail += 1;
+
+                include_locators!(
+                    config,
+                    status,
+                    value.variable.name,
+                    value.variable.index,
+                    value.row,
+                    format!("{:?}", text),
+                    format!("{} in string value", kinds.join(", "))
+                );
+            }
+        }
+    }
+}
+
+/// Check string values for mojibake, noting the declared file encoding
+fn string_value_mojibake(value: &Value, config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.mojibake {
+        use check::CheckName::Mojibake;
+        include_check!(
+            report.summary,
+            Mojibake,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        if !setting.setting
+            || value.missing != Missing::NOT_MISSING
+            || value.variable.type_ != VariableType::Text
+        {
+            return;
+        }
+
+        let file_encoding = report.metadata.file_encoding.clone();
+
+        if let Some(ref mut status) = report.summary.get_mut(&Mojibake) {
+            let text = format!("{}", value.value);
+
+            match mojibake(&text) {
+                None => status.pass += 1,
+                Some(sequence) => {
+                    status.fail += 1;
+
+                    include_locators!(
+                        config,
+                        status,
+                        value.variable.name,
+                        value.variable.index,
+                        value.row,
+                        format!("{:?}", text),
+                        mojibake_note(&sequence, "string value", &file_encoding)
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Check non-missing numeric values fall within the ranges and allowed
+/// values configured for any pattern matching their variable name. The
+/// first range broken, in config order, is noted on the locator
+fn value_out_of_range(value: &Value, config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.value_range {
+        use check::CheckName::ValueOutOfRange;
+        include_check!(
+            report.summary,
+            ValueOutOfRange,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        // values are numeric when they parse, whatever the variable type,
+        // as CSV columns are all read as text
+        let number = match value.value.as_f64() {
+            Some(number) if value.missing == Missing::NOT_MISSING => number,
+            _ => return,
+        };
+
+        let ranges = setting.setting.matching(&value.variable.name);
+
+        if ranges.is_empty() {
+            return;
+        }
+
+        if let Some(ref mut status) = report.summary.get_mut(&ValueOutOfRange) {
+            match ranges.iter().find(|range| !range.contains(number)) {
+                Some(range) => {
+                    status.fail += 1;
+
+                    include_locators!(
+                        config,
+                        status,
+                        value.variable.name,
+                        value.variable.index,
+                        value.row,
+                        value.value,
+                        format!("outside {}", range)
+                    );
+                }
+                None => status.pass += 1,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use config::Setting;
+    use config::{Setting, ValueRange, ValueRanges};
     use model::anyvalue::AnyValue;
-    use model::variable::{Variable, VariableType};
+
+    use model::variable::{Alignment, Measure, Variable};
 
     fn setup() -> (Value, Config, Report) {
         let value = Value {
@@ -60,6 +199,12 @@ mod tests {
                 type_: VariableType::Text,
                 value_format: String::new(),
                 value_labels: String::new(),
+                missing_ranges: vec![],
+                measure: Measure::Unknown,
+                alignment: Alignment::Unknown,
+                display_width: 0,
+                storage_width: 0,
+                decimals: None,
             },
             row: 1,
             value: AnyValue::from("foo"),
@@ -96,4 +241,134 @@ mod tests {
         value_defined_missing_no_label(&value, &config, &mut report);
         assert_setting!(report.summary.get_mut(&ValueDefinedMissingNoLabel), 1, 1);
     }
+
+    #[test]
+    fn test_string_value_invisible_characters() {
+        use check::CheckName::InvisibleCharacters;
+        let (mut value, mut config, mut report) = setup();
+
+        config.data_integrity.invisible_characters = Some(Setting {
+            setting: true,
+            desc: "description from config".to_string(),
+        });
+
+        string_value_invisible_characters(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&InvisibleCharacters), 1, 0);
+
+        value.value = AnyValue::from("foo\u{200B}");
+        string_value_invisible_characters(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&InvisibleCharacters), 1, 1);
+
+        // numeric variables are not checked
+        value.variable.type_ = VariableType::Numeric;
+        string_value_invisible_characters(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&InvisibleCharacters), 1, 1);
+    }
+
+    #[test]
+    fn test_string_value_mojibake() {
+        use check::CheckName::Mojibake;
+        let (mut value, mut config, mut report) = setup();
+
+        config.metadata_only = Some(false);
+        config.data_integrity.mojibake = Some(Setting {
+            setting: true,
+            desc: "description from config".to_string(),
+        });
+        report.metadata.file_encoding = Some("WINDOWS-1252".to_string());
+
+        string_value_mojibake(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&Mojibake), 1, 0);
+
+        value.value = AnyValue::from("cafÃ©");
+        string_value_mojibake(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&Mojibake), 1, 1);
+
+        let notes = report
+            .summary
+            .get(&Mojibake)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.note.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            vec!["\"Ã©\" in string value, file encoding WINDOWS-1252".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_value_out_of_range() {
+        use check::CheckName::ValueOutOfRange;
+        let (mut value, mut config, mut report) = setup();
+
+        config.metadata_only = Some(false);
+        config.data_integrity.value_range = Some(Setting {
+            setting: ValueRanges(vec![
+                (
+                    "te*".to_string(),
+                    ValueRange {
+                        min: Some(0.0),
+                        max: Some(120.0),
+                        allowed: None,
+                    },
+                ),
+                (
+                    "test".to_string(),
+                    ValueRange {
+                        min: None,
+                        max: None,
+                        allowed: Some(vec![1.0, 2.0, 3.0]),
+                    },
+                ),
+            ]),
+            desc: "description from config".to_string(),
+        });
+
+        // text values which don't parse as numbers are not checked
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 0, 0);
+
+        // numbers are checked whatever the variable type, e.g. CSV columns
+        value.value = AnyValue::from("2");
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 1, 0);
+
+        value.row = 2;
+        value.value = AnyValue::from(150);
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 1, 1);
+
+        value.row = 3;
+        value.value = AnyValue::from(4);
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 1, 2);
+
+        // missing values are excluded
+        value.missing = Missing::SYSTEM_MISSING;
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 1, 2);
+
+        // variables not matching any pattern are ignored
+        value.missing = Missing::NOT_MISSING;
+        value.variable.name = "other".to_string();
+        value_out_of_range(&value, &config, &mut report);
+        assert_setting!(report.summary.get(&ValueOutOfRange), 1, 2);
+
+        // the first range broken, in config order, is noted
+        let notes = report
+            .summary
+            .get(&ValueOutOfRange)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.value_index, l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            vec![
+                (2, "outside min 0, max 120".to_string()),
+                (3, "outside allowed [1.0, 2.0, 3.0]".to_string()),
+            ]
+        );
+    }
 }
@@ -1,10 +1,8 @@
-use check::{contains, VariableCheckFn};
+use check::{contains, invisible_characters, mojibake, mojibake_note, VariableCheckFn};
 use config::Config;
 use model::variable::Variable;
 use report::{Category, Locator, Report, Status};
 
-use std::collections::HashSet;
-
 /// Register the checks
 pub fn register() -> Vec<VariableCheckFn> {
     vec![
@@ -12,6 +10,8 @@ pub fn register() -> Vec<VariableCheckFn> {
         missing_variable_labels,
         variable_label_max_length,
         variable_odd_characters,
+        variable_invisible_characters,
+        variable_label_mojibake,
     ]
 }
 
@@ -102,12 +102,26 @@ fn variable_odd_characters(variable: &Variable, config: &Config, report: &mut Re
         );
 
         if let Some(ref mut status) = report.summary.get_mut(&VariableOddCharacters) {
-            if contains(&variable.name, &setting.setting)
-                || contains(&variable.label, &setting.setting)
-            {
+            let found = match setting.setting.find(&variable.name) {
+                Some(found) => Some((&variable.name, found, "name")),
+                None => setting
+                    .setting
+                    .find(&variable.label)
+                    .map(|found| (&variable.label, found, "label")),
+            };
+
+            if let Some((text, found, source)) = found {
                 status.fail += 1;
 
-                include_locators!(config, status, variable.name, variable.index, -1);
+                include_locators!(
+                    config,
+                    status,
+                    variable.name,
+                    variable.index,
+                    -1,
+                    text,
+                    format!("{} in {}", found, source)
+                );
             } else {
                 status.pass += 1;
             }
@@ -115,11 +129,95 @@ fn variable_odd_characters(variable: &Variable, config: &Config, report: &mut Re
     }
 }
 
+/// Check variable names and labels for stray whitespace and invisible
+/// characters, each kind found is noted on the locator
+fn variable_invisible_characters(variable: &Variable, config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.invisible_characters {
+        use check::CheckName::InvisibleCharacters;
+        include_check!(
+            report.summary,
+            InvisibleCharacters,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        if !setting.setting {
+            return;
+        }
+
+        if let Some(ref mut status) = report.summary.get_mut(&InvisibleCharacters) {
+            let texts = vec![
+                (&variable.name, "variable name"),
+                (&variable.label, "variable label"),
+            ];
+
+            for (text, source) in texts {
+                let kinds = invisible_characters(text);
+
+                if kinds.is_empty() {
+                    status.pass += 1;
+                    continue;
+                }
+
+                status.fail += 1;
+
+                include_locators!(
+                    config,
+                    status,
+                    variable.name,
+                    variable.index,
+                    -1,
+                    format!("{:?}", text),
+                    format!("{} in {}", kinds.join(", "), source)
+                );
+            }
+        }
+    }
+}
+
+/// Check variable labels for mojibake, noting the declared file encoding
+fn variable_label_mojibake(variable: &Variable, config: &Config, report: &mut Report) {
+    if let Some(ref setting) = config.data_integrity.mojibake {
+        use check::CheckName::Mojibake;
+        include_check!(
+            report.summary,
+            Mojibake,
+            &setting.desc,
+            Category::DataIntegrity
+        );
+
+        if !setting.setting {
+            return;
+        }
+
+        let file_encoding = report.metadata.file_encoding.clone();
+
+        if let Some(ref mut status) = report.summary.get_mut(&Mojibake) {
+            match mojibake(&variable.label) {
+                None => status.pass += 1,
+                Some(sequence) => {
+                    status.fail += 1;
+
+                    include_locators!(
+                        config,
+                        status,
+                        variable.name,
+                        variable.index,
+                        -1,
+                        format!("{:?}", variable.label),
+                        mojibake_note(&sequence, "variable label", &file_encoding)
+                    );
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use config::{Config, Setting};
+    use config::{Config, OddCharacters, Setting};
     use report::Report;
 
     fn setup() -> (Variable, Config, Report) {
@@ -146,10 +244,13 @@ mod test {
         });
 
         config.metadata.variable_odd_characters = Some(Setting {
-            setting: vec!["#", "@"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
+            setting: OddCharacters::new(
+                vec!["#", "@"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>(),
+            )
+            .unwrap(),
             desc: String::from("variable odd characters"),
         });
 
@@ -221,4 +322,86 @@ mod test {
         variable_odd_characters(&variable, &config, &mut report);
         assert_setting!(report.summary.get(&VariableOddCharacters), 1, 2);
     }
+
+    #[test]
+    fn test_variable_odd_characters_patterns() {
+        let (mut variable, mut config, mut report) = setup();
+        use check::CheckName::VariableOddCharacters;
+
+        assert!(OddCharacters::new(vec!["regex:([".to_string()]).is_err());
+
+        config.metadata_only = Some(false);
+        config.metadata.variable_odd_characters = Some(Setting {
+            setting: OddCharacters::new(vec![
+                "\\p{Cc}".to_string(),
+                "regex:[^[:ascii:]]".to_string(),
+            ])
+            .unwrap(),
+            desc: String::from("variable odd characters"),
+        });
+
+        variable.label = String::from("café");
+        variable_odd_characters(&variable, &config, &mut report);
+        assert_setting!(report.summary.get(&VariableOddCharacters), 0, 1);
+
+        let locators = report
+            .summary
+            .get(&VariableOddCharacters)
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.value.clone().unwrap(), l.note.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            locators,
+            vec![("café".to_string(), "\"é\" matches regex:[^[:ascii:]] in label".to_string())]
+        );
+
+        // allow entries add to the permitted characters
+        let odd_characters = OddCharacters::new(vec![
+            "#".to_string(),
+            "allow:[a-z]".to_string(),
+            "allow:[0-9_]".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(odd_characters.find("var_1"), None);
+        assert_eq!(odd_characters.find("var#1"), Some("\"#\"".to_string()));
+        assert_eq!(
+            odd_characters.find("Var_1"),
+            Some("'V' is not allowed by allow:[a-z], allow:[0-9_]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_variable_invisible_characters() {
+        let (mut variable, mut config, mut report) = setup();
+        use check::CheckName::InvisibleCharacters;
+
+        config.metadata_only = Some(false);
+        config.data_integrity.invisible_characters = Some(Setting {
+            setting: true,
+            desc: String::from("invisible characters"),
+        });
+
+        assert!(report.summary.get(&InvisibleCharacters).is_none());
+
+        variable.label = String::from("fine label");
+        variable_invisible_characters(&variable, &config, &mut report);
+        assert_setting!(report.summary.get(&InvisibleCharacters), 2, 0);
+
+        variable.label = String::from("label\u{00A0} ");
+        variable_invisible_characters(&variable, &config, &mut report);
+        assert_setting!(report.summary.get(&InvisibleCharacters), 3, 1);
+
+        let notes = report
+            .summary
+            .get(&InvisibleCharacters)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.note.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            vec!["trailing whitespace, non-breaking space in variable label"]
+        );
+    }
 }
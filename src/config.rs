@@ -1,5 +1,15 @@
+use std::fmt;
 use std::path::Path;
-use check::CheckName;
+use check::{glob_match, CheckName};
+use check::expression::Expression;
+use model::missing::Missing;
+
+use regex::Regex;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Number of locators kept per check when `locator_limit` is not set
+pub const DEFAULT_LOCATOR_LIMIT: usize = 1000;
 
 pub trait Valid {
     fn validate(&self) -> Result<(), &'static str>;
@@ -23,6 +33,9 @@ pub struct Setting<T> {
 pub struct Config {
     pub metadata_only: Option<bool>,
     pub progress: Option<bool>,
+    pub locator_limit: Option<i32>,
+    pub encoding: Option<String>, // character encoding of the input file, overrides the encoding it declares
+    pub csv: Option<CsvDialect>,
 
     pub basic_file_checks: BasicFileChecks,
     pub metadata: Metadata,
@@ -31,6 +44,22 @@ pub struct Config {
 }
 
 impl Config {
+    /// Maximum number of locators to record for each check
+    pub fn max_locators(&self) -> usize {
+        match self.locator_limit {
+            Some(limit) => limit as usize,
+            None => DEFAULT_LOCATOR_LIMIT,
+        }
+    }
+
+    /// Types of missing value counted by the missing value threshold
+    pub fn missing_value_types(&self) -> Vec<MissingType> {
+        match self.data_integrity.missing_value_types {
+            Some(ref types) => types.clone(),
+            None => vec![MissingType::System, MissingType::Defined, MissingType::Tagged],
+        }
+    }
+
     pub fn get_desc(&self, check_name: &CheckName) -> &str {
         if let Some(ref setting) = self.config_for_check(check_name) {
             return &setting.desc;
@@ -59,6 +88,32 @@ impl Config {
         return result;
     }
 
+    /// Combinations of variables whose values must be tracked row by row
+    pub fn tracked_variable_sets(&self) -> Vec<Vec<String>> {
+        let mut result: Vec<Vec<String>> = vec![];
+
+        if let Some(ref keys) = self.data_integrity.duplicate_compound_keys {
+            result.extend(keys.setting.iter().cloned());
+        }
+
+        if let Some(ref k_anonymity) = self.disclosure_risk.k_anonymity {
+            result.extend(k_anonymity.setting.quasi_identifiers.iter().cloned());
+        }
+
+        if let Some(ref l_diversity) = self.disclosure_risk.l_diversity {
+            result.extend(l_diversity.setting.groups());
+        }
+
+        if let Some(ref special_uniques) = self.disclosure_risk.special_uniques {
+            result.push(special_uniques.setting.key_variables.clone());
+        }
+
+        result.sort();
+        result.dedup();
+
+        return result;
+    }
+
     pub fn config_for_check(&self, check_name: &CheckName) -> &Option<Setting<Vec<String>>> {
         return match check_name {
             CheckName::VariableLabelSpellcheck => &self.metadata.variable_label_spellcheck,
@@ -73,6 +128,28 @@ impl Config {
 
 impl Valid for Config {
     fn validate(&self) -> Result<(), &'static str> {
+        match self.locator_limit {
+            None => (),
+            Some(limit) => {
+                if limit < 1 {
+                    return Err("locator_limit must be greater than 0");
+                }
+            }
+        }
+
+        match self.encoding {
+            None => (),
+            Some(ref encoding) => {
+                if encoding.trim().is_empty() {
+                    return Err("encoding must not be empty");
+                }
+            }
+        }
+
+        if let Some(ref csv) = self.csv {
+            csv.validate()?;
+        }
+
         self.basic_file_checks.validate()?;
         self.metadata.validate()?;
         self.data_integrity.validate()?;
@@ -82,6 +159,65 @@ impl Valid for Config {
     }
 }
 
+/// How CSV files are read, the delimiter is sniffed from the first lines
+/// when not set
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CsvDialect {
+    pub delimiter: Option<char>,
+    pub quote: Option<char>,
+    pub escape: Option<char>, // quotes are escaped by doubling them when not set
+    pub has_headers: Option<bool>, // variables are named V1..Vn when false
+    pub skip_rows: Option<i32>, // lines skipped before the header or first record
+    pub comment: Option<char>,
+    pub trim: Option<bool>, // trims whitespace around headers and fields
+}
+
+impl CsvDialect {
+    pub fn has_headers(&self) -> bool {
+        self.has_headers.unwrap_or(true)
+    }
+
+    pub fn skip_rows(&self) -> usize {
+        self.skip_rows.unwrap_or(0) as usize
+    }
+}
+
+impl Valid for CsvDialect {
+    fn validate(&self) -> Result<(), &'static str> {
+        let characters = vec![self.delimiter, self.quote, self.escape, self.comment];
+
+        if characters
+            .iter()
+            .any(|c| c.map_or(false, |c| !c.is_ascii()))
+        {
+            return Err("csv delimiter, quote, escape and comment must be ASCII characters");
+        }
+
+        if self.delimiter.is_some()
+            && (self.delimiter == self.quote
+                || self.delimiter == self.escape
+                || self.delimiter == self.comment)
+        {
+            return Err("csv delimiter must differ from the quote, escape and comment characters");
+        }
+
+        if self.delimiter == Some('\n') || self.delimiter == Some('\r') {
+            return Err("csv delimiter must not be a line break");
+        }
+
+        match self.skip_rows {
+            None => (),
+            Some(rows) => {
+                if rows < 0 {
+                    return Err("csv skip_rows must be 0 or greater");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct BasicFileChecks {
     pub bad_filename: Option<Setting<String>>,
@@ -107,15 +243,19 @@ pub struct Metadata {
     pub primary_variable: Option<Setting<String>>,
 
     pub missing_variable_labels: Option<Setting<bool>>,
-    pub variable_odd_characters: Option<Setting<Vec<String>>>,
+    pub variable_odd_characters: Option<Setting<OddCharacters>>,
     pub variable_label_max_length: Option<Setting<i32>>,
     pub variable_label_spellcheck: Option<Setting<Vec<String>>>,
 
-    pub value_label_odd_characters: Option<Setting<Vec<String>>>,
+    pub value_label_odd_characters: Option<Setting<OddCharacters>>,
     pub value_label_max_length: Option<Setting<i32>>,
     pub value_label_spellcheck: Option<Setting<Vec<String>>>,
 
     pub value_defined_missing_no_label: Option<Setting<bool>>, // SPSS only. E.g. -9 is Defined missing but has no label
+    pub values_without_labels: Option<Setting<bool>>, // E.g. 7 found in a variable labelled 1-5
+    pub unused_value_labels: Option<Setting<bool>>,
+    pub missing_value_codes: Option<Setting<Vec<f64>>>, // SPSS only. Empty compares with the most common codes
+    pub measure_consistency: Option<Setting<MeasureConsistency>>, // SPSS only
 }
 
 impl Valid for Metadata {
@@ -165,6 +305,21 @@ impl Valid for Metadata {
             }
         }
 
+        match self.measure_consistency {
+            None => (),
+            Some(ref measure) => {
+                let measure = &measure.setting;
+
+                if !(measure.scale_labelled_percentage > 0 && measure.scale_labelled_percentage <= 100) {
+                    return Err("metadata.measure_consistency.scale_labelled_percentage out of bounds, must be between 1 and 100 inclusive");
+                }
+
+                if measure.nominal_max_distinct < 1 {
+                    return Err("metadata.measure_consistency.nominal_max_distinct must be at least 1");
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -172,10 +327,20 @@ impl Valid for Metadata {
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DataIntegrity {
     pub duplicate_values: Option<Setting<Vec<String>>>,
+    pub duplicate_compound_keys: Option<Setting<Vec<Vec<String>>>>,
+    pub duplicate_records: Option<Setting<Vec<String>>>, // variables excluded from the comparison, e.g. IDs
+    pub value_range: Option<Setting<ValueRanges>>,
+    pub outliers: Option<Setting<Outliers>>,
+    pub consistency_rules: Option<Setting<Vec<ConsistencyRule>>>,
 
-    pub string_value_odd_characters: Option<Setting<Vec<String>>>,
+    pub string_value_odd_characters: Option<Setting<OddCharacters>>,
     pub string_value_spellcheck: Option<Setting<Vec<String>>>,
+    pub invisible_characters: Option<Setting<bool>>, // names, labels and string values
+    pub mojibake: Option<Setting<bool>>, // labels and string values
     pub system_missing_value_threshold: Option<Setting<i32>>,
+    pub missing_value_types: Option<Vec<MissingType>>, // counted by the threshold, all types when not set
+    pub record_missing_threshold: Option<Setting<i32>>,
+    pub constant_variables: Option<Setting<Vec<String>>>, // variables exempt from the check, e.g. survey wave
 }
 
 impl Valid for DataIntegrity {
@@ -189,6 +354,66 @@ impl Valid for DataIntegrity {
             }
         }
 
+        match self.duplicate_compound_keys {
+            None => (),
+            Some(ref keys) => {
+                if keys.setting.len() < 1 || keys.setting.iter().any(|key| key.len() < 1) {
+                    return Err("data_integrity.duplicate_compound_keys cannot be empty or contain empty keys");
+                }
+            }
+        }
+
+        match self.value_range {
+            None => (),
+            Some(ref ranges) => {
+                if ranges.setting.len() < 1 {
+                    return Err("data_integrity.value_range cannot be empty");
+                }
+
+                for (_, range) in ranges.setting.iter() {
+                    if range.min.is_none() && range.max.is_none() && range.allowed.is_none() {
+                        return Err("data_integrity.value_range entries must set min, max or allowed");
+                    }
+
+                    if let (Some(min), Some(max)) = (range.min, range.max) {
+                        if min > max {
+                            return Err("data_integrity.value_range min cannot be greater than max");
+                        }
+                    }
+                }
+            }
+        }
+
+        match self.outliers {
+            None => (),
+            Some(ref outliers) => {
+                if !(outliers.setting.threshold > 0.0) {
+                    return Err("data_integrity.outliers.threshold must be greater than 0");
+                }
+
+                if let Some(ref variables) = outliers.setting.variables {
+                    if variables.len() < 1 {
+                        return Err("data_integrity.outliers.variables cannot be empty");
+                    }
+                }
+            }
+        }
+
+        match self.consistency_rules {
+            None => (),
+            Some(ref rules) => {
+                if rules.setting.len() < 1 {
+                    return Err("data_integrity.consistency_rules cannot be empty");
+                }
+
+                for rule in rules.setting.iter() {
+                    if rule.name.is_empty() {
+                        return Err("data_integrity.consistency_rules must each have a name");
+                    }
+                }
+            }
+        }
+
         match self.string_value_odd_characters {
             None => (),
             Some(ref odd_characters) => {
@@ -207,6 +432,24 @@ impl Valid for DataIntegrity {
             }
         }
 
+        match self.missing_value_types {
+            None => (),
+            Some(ref types) => {
+                if types.len() < 1 {
+                    return Err("data_integrity.missing_value_types cannot be empty");
+                }
+            }
+        }
+
+        match self.record_missing_threshold {
+            None => (),
+            Some(ref threshold) => {
+                if !(threshold.setting > 0 && threshold.setting <= 100) {
+                    return Err("data_integrity.record_missing_threshold out of bounds, must be between 1 and 100 inclusive");
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -217,6 +460,9 @@ pub struct DisclosureRisk {
 
     pub regex_patterns: Option<Setting<Vec<String>>>,
     pub unique_values: Option<Setting<i32>>,
+    pub k_anonymity: Option<Setting<KAnonymity>>,
+    pub l_diversity: Option<Setting<LDiversity>>,
+    pub special_uniques: Option<Setting<SpecialUniques>>,
 
     pub string_value_stopword: Option<Setting<Vec<String>>>,
 }
@@ -241,6 +487,441 @@ impl Valid for DisclosureRisk {
             }
         }
 
+        match self.k_anonymity {
+            None => (),
+            Some(ref k_anonymity) => {
+                let sets = &k_anonymity.setting.quasi_identifiers;
+                if sets.len() < 1 || sets.iter().any(|set| set.len() < 1) {
+                    return Err("disclosure_risk.k_anonymity.quasi_identifiers cannot be empty or contain empty sets");
+                }
+
+                if k_anonymity.setting.k < 2 {
+                    return Err("disclosure_risk.k_anonymity.k must be at least 2");
+                }
+            }
+        }
+
+        match self.l_diversity {
+            None => (),
+            Some(ref l_diversity) => {
+                if l_diversity.setting.quasi_identifiers.len() < 1 {
+                    return Err("disclosure_risk.l_diversity.quasi_identifiers cannot be empty");
+                }
+
+                if l_diversity.setting.sensitive_variables.len() < 1 {
+                    return Err("disclosure_risk.l_diversity.sensitive_variables cannot be empty");
+                }
+
+                if l_diversity.setting.l < 2 {
+                    return Err("disclosure_risk.l_diversity.l must be at least 2");
+                }
+            }
+        }
+
+        match self.special_uniques {
+            None => (),
+            Some(ref special_uniques) => {
+                if special_uniques.setting.key_variables.len() < 1 {
+                    return Err("disclosure_risk.special_uniques.key_variables cannot be empty");
+                }
+
+                if special_uniques.setting.max_subset_size < 1 {
+                    return Err("disclosure_risk.special_uniques.max_subset_size must be at least 1");
+                }
+
+                if special_uniques.setting.top < 1 {
+                    return Err("disclosure_risk.special_uniques.top must be at least 1");
+                }
+            }
+        }
+
         Ok(())
     }
 }
+
+/// Quasi-identifier sets and the minimum size of each equivalence class
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KAnonymity {
+    pub quasi_identifiers: Vec<Vec<String>>,
+    pub k: i32,
+}
+
+/// Quasi-identifiers, the sensitive variables to compare within each group
+/// and the minimum number of distinct sensitive values per group
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LDiversity {
+    pub quasi_identifiers: Vec<String>,
+    pub sensitive_variables: Vec<String>,
+    pub l: i32,
+}
+
+impl LDiversity {
+    /// The quasi-identifiers followed by a sensitive variable, one
+    /// combination for each sensitive variable
+    pub fn groups(&self) -> Vec<Vec<String>> {
+        self.sensitive_variables
+            .iter()
+            .map(|sensitive| {
+                let mut group = self.quasi_identifiers.clone();
+                group.push(sensitive.clone());
+                group
+            })
+            .collect()
+    }
+}
+
+/// Key variables to search for minimal sample uniques, the largest subset
+/// of key variables to consider and the number of riskiest records to report
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SpecialUniques {
+    pub key_variables: Vec<String>,
+    pub max_subset_size: i32,
+    pub top: i32,
+}
+
+/// Characters which must not appear in names, labels or values. Each entry
+/// is one of
+///
+/// - a literal string, e.g. `#` or two spaces
+/// - a Unicode class, e.g. `\p{Cc}` for control characters
+/// - `regex:` followed by a regex pattern, e.g. `regex:[^[:ascii:]]`
+/// - `allow:` followed by a regex matching permitted characters, e.g.
+///   `allow:[\p{L}\p{N} _.]`, any other character is odd. Every `allow:`
+///   entry adds to the one set of permitted characters
+///
+/// Entries are compiled once, when the config is read.
+#[derive(Clone)]
+pub struct OddCharacters {
+    entries: Vec<String>,
+    rules: Vec<(CharacterRule, String)>, // each rule and the entries it was compiled from
+}
+
+#[derive(Clone)]
+enum CharacterRule {
+    Literal(String),
+    Pattern(Regex),
+    Allow(Regex),
+}
+
+impl OddCharacters {
+    pub fn new(entries: Vec<String>) -> Result<OddCharacters, String> {
+        let compile = |pattern: &str, entry: &str| {
+            Regex::new(pattern).map_err(|err| format!("invalid pattern {:?}, {}", entry, err))
+        };
+
+        let mut rules = vec![];
+        let mut allowed: Vec<&String> = vec![];
+        let mut allowed_position = 0; // the merged rule takes the place of the first allow entry
+
+        for entry in entries.iter() {
+            if entry.starts_with("regex:") {
+                let re = compile(&entry["regex:".len()..], entry)?;
+                rules.push((CharacterRule::Pattern(re), entry.clone()));
+            } else if entry.starts_with("allow:") {
+                // checked on its own, then merged with the other allow entries
+                compile(&entry["allow:".len()..], entry)?;
+
+                if allowed.is_empty() {
+                    allowed_position = rules.len();
+                }
+                allowed.push(entry);
+            } else if entry.starts_with("\\p{") || entry.starts_with("\\P{") {
+                rules.push((CharacterRule::Pattern(compile(entry, entry)?), entry.clone()));
+            } else {
+                rules.push((CharacterRule::Literal(entry.clone()), entry.clone()));
+            }
+        }
+
+        if !allowed.is_empty() {
+            let alternation = allowed
+                .iter()
+                .map(|entry| format!("(?:{})", &entry["allow:".len()..]))
+                .collect::<Vec<String>>()
+                .join("|");
+            let names = allowed
+                .iter()
+                .map(|entry| entry.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ");
+
+            let re = compile(&format!("^(?:{})$", alternation), &names)?;
+            rules.insert(allowed_position, (CharacterRule::Allow(re), names));
+        }
+
+        Ok(OddCharacters {
+            entries: entries,
+            rules: rules,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Describes the first odd character, or pattern, found in the text
+    pub fn find(&self, text: &str) -> Option<String> {
+        self.rules
+            .iter()
+            .filter_map(|(rule, entry)| match rule {
+                CharacterRule::Literal(literal) => {
+                    if text.contains(literal.as_str()) {
+                        Some(format!("{:?}", literal))
+                    } else {
+                        None
+                    }
+                }
+                CharacterRule::Pattern(re) => re
+                    .find(text)
+                    .map(|m| format!("{:?} matches {}", m.as_str(), entry)),
+                CharacterRule::Allow(re) => text
+                    .chars()
+                    .find(|c| !re.is_match(&c.to_string()))
+                    .map(|c| format!("{:?} is not allowed by {}", c, entry)),
+            })
+            .next()
+    }
+}
+
+impl Serialize for OddCharacters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OddCharacters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<String>::deserialize(deserializer)?;
+        OddCharacters::new(entries).map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Debug for OddCharacters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.entries)
+    }
+}
+
+/// Thresholds at which the measurement level of a variable is inconsistent
+/// with its labels and values
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MeasureConsistency {
+    /// Scale variables fail when at least this percentage of their distinct
+    /// values have a value label
+    pub scale_labelled_percentage: i32,
+    /// Nominal numeric variables fail with more distinct values than this
+    pub nominal_max_distinct: i32,
+    /// Whether string variables fail when their measure is ordinal
+    pub ordinal_strings: bool,
+}
+
+/// Ranges keyed by variable name or pattern, in the order they are given in
+/// the config file
+#[derive(Clone, Debug, Default)]
+pub struct ValueRanges(pub Vec<(String, ValueRange)>);
+
+impl ValueRanges {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, (String, ValueRange)> {
+        self.0.iter()
+    }
+
+    /// Ranges whose pattern matches the variable name, in config order
+    pub fn matching(&self, name: &str) -> Vec<&ValueRange> {
+        self.0
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, name))
+            .map(|(_, range)| range)
+            .collect()
+    }
+}
+
+impl Serialize for ValueRanges {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(pattern, range)| (pattern, range)))
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueRanges {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RangesVisitor;
+
+        impl<'de> Visitor<'de> for RangesVisitor {
+            type Value = ValueRanges;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a map of variable names or patterns to ranges")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<ValueRanges, M::Error> {
+                let mut ranges = vec![];
+
+                while let Some(entry) = map.next_entry::<String, ValueRange>()? {
+                    ranges.push(entry);
+                }
+
+                Ok(ValueRanges(ranges))
+            }
+        }
+
+        deserializer.deserialize_map(RangesVisitor)
+    }
+}
+
+/// Bounds and allowed values for the numeric values of a variable
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValueRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub allowed: Option<Vec<f64>>,
+}
+
+impl ValueRange {
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.map_or(true, |min| value >= min)
+            && self.max.map_or(true, |max| value <= max)
+            && self
+                .allowed
+                .as_ref()
+                .map_or(true, |allowed| allowed.contains(&value))
+    }
+}
+
+impl fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts: Vec<String> = vec![];
+
+        if let Some(min) = self.min {
+            parts.push(format!("min {}", min));
+        }
+
+        if let Some(max) = self.max {
+            parts.push(format!("max {}", max));
+        }
+
+        if let Some(ref allowed) = self.allowed {
+            parts.push(format!("allowed {:?}", allowed));
+        }
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// A named rule every row must satisfy, written in the expression language
+/// of `check::expression`, e.g. `if employed == 2 then missing(hours_worked)`.
+/// The rule is parsed once, when the config is read.
+#[derive(Clone, Debug)]
+pub struct ConsistencyRule {
+    pub name: String,
+    pub rule: String,
+    expression: Expression,
+    variables: Vec<String>,
+}
+
+/// A consistency rule as written in the config file
+#[derive(Serialize, Deserialize)]
+struct ConsistencyRuleEntry {
+    name: String,
+    rule: String,
+}
+
+impl ConsistencyRule {
+    pub fn new(name: &str, rule: &str) -> Result<ConsistencyRule, String> {
+        let expression = Expression::parse(rule)
+            .map_err(|err| format!("rule {:?} cannot be parsed, {}", name, err))?;
+
+        Ok(ConsistencyRule {
+            name: name.to_string(),
+            rule: rule.to_string(),
+            variables: expression.variables(),
+            expression: expression,
+        })
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    /// Variables the rule refers to, in order of first use
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+}
+
+impl Serialize for ConsistencyRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ConsistencyRuleEntry {
+            name: self.name.clone(),
+            rule: self.rule.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConsistencyRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entry = ConsistencyRuleEntry::deserialize(deserializer)?;
+        ConsistencyRule::new(&entry.name, &entry.rule).map_err(de::Error::custom)
+    }
+}
+
+/// Type of missing value, see `model::missing::Missing`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingType {
+    System,
+    Defined,
+    Tagged,
+}
+
+impl MissingType {
+    pub fn matches(&self, missing: &Missing) -> bool {
+        match (self, missing) {
+            (MissingType::System, Missing::SYSTEM_MISSING) => true,
+            (MissingType::Defined, Missing::DEFINED_MISSING) => true,
+            (MissingType::Tagged, Missing::TAGGED_MISSING(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Method used to derive the fences of the outlier check
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierMethod {
+    /// Quartiles -/+ threshold times the interquartile range
+    Iqr,
+    /// Mean -/+ threshold times the standard deviation
+    ZScore,
+    /// Median -/+ threshold times the scaled median absolute deviation
+    Mad,
+}
+
+impl fmt::Display for OutlierMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutlierMethod::Iqr => write!(f, "IQR"),
+            OutlierMethod::ZScore => write!(f, "z-score"),
+            OutlierMethod::Mad => write!(f, "MAD"),
+        }
+    }
+}
+
+/// Outlier detection method and threshold, optionally limited to the
+/// numeric variables matching the given name patterns
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Outliers {
+    pub method: OutlierMethod,
+    pub threshold: f64,
+    pub variables: Option<Vec<String>>,
+}
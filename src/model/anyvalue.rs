@@ -78,6 +78,22 @@ impl From<i32> for AnyValue {
     }
 }
 
+impl AnyValue {
+    /// The value as a number, strings are parsed if possible
+    pub fn as_f64(&self) -> Option<f64> {
+        use self::AnyValue::*;
+
+        match self {
+            &Str(ref value) => value.trim().parse::<f64>().ok(),
+            &Int8(ref value) => Some(**value as f64),
+            &Int16(ref value) => Some(**value as f64),
+            &Int32(ref value) => Some(**value as f64),
+            &Float(ref value) => Some(**value as f64),
+            &Double(ref value) => Some(**value),
+        }
+    }
+}
+
 /// Hash trait allows for use a key in a HashMap
 impl Hash for AnyValue {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -101,6 +117,12 @@ pub fn calculate_hash<T: Hash>(t: &T) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
 
+    #[test]
+    fn test_as_f64() {
+        assert_eq!(AnyValue::from(42).as_f64(), Some(42.0));
+        assert_eq!(AnyValue::from(" 1.5").as_f64(), Some(1.5));
+        assert_eq!(AnyValue::from("foo").as_f64(), None);
+    }
 }
@@ -26,16 +26,19 @@ impl<'a> From<&'a str> for Value {
     }
 }
 
-/// Hash implemtation distiguishes values based on `value` field ONLY
+/// Hash implemtation distiguishes values based on `value` and `missing`
+/// fields ONLY. System and tagged missing values display alike, so the kind
+/// of missing keeps them apart
 impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.value.hash(state);
+        self.missing.hash(state);
     }
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Value) -> bool {
-        self.value.eq(&other.value)
+        self.value.eq(&other.value) && self.missing == other.missing
     }
 }
 
@@ -1,10 +1,13 @@
+use model::anyvalue::AnyValue;
 use readstat::bindings::*;
 
 use std::ffi::CStr;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::os::raw::c_char;
 use std::ptr;
 
-#[derive(Serialize, Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Serialize, Debug, Clone)]
 pub struct Variable {
     pub index: i32,
     pub name: String,
@@ -12,6 +15,46 @@ pub struct Variable {
     pub type_: VariableType,
     pub value_format: String,
     pub value_labels: String,
+    pub missing_ranges: Vec<MissingRange>, // user-defined missing values, SPSS only
+    pub measure: Measure,
+    pub alignment: Alignment,
+    pub display_width: i32,
+    pub storage_width: i32,
+    pub decimals: Option<i32>, // parsed from the format, e.g. 2 for F8.2 or %9.2f
+}
+
+/// Variables are identified by their index and name alone, so a variable is
+/// still found, e.g. in the frequency table, when its other metadata changes
+impl Hash for Variable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.name.hash(state);
+    }
+}
+
+impl PartialEq for Variable {
+    fn eq(&self, other: &Variable) -> bool {
+        self.index == other.index && self.name == other.name
+    }
+}
+
+impl Eq for Variable {}
+
+/// A user-defined missing value, or range of values when `lo` and `hi` differ
+#[derive(Serialize, Debug, Clone, Hash, PartialEq, Eq)]
+pub struct MissingRange {
+    pub lo: AnyValue,
+    pub hi: AnyValue,
+}
+
+impl fmt::Display for MissingRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.lo == self.hi {
+            write!(f, "{}", self.lo)
+        } else {
+            write!(f, "{} thru {}", self.lo, self.hi)
+        }
+    }
 }
 
 impl Variable {
@@ -41,6 +84,15 @@ impl Variable {
                 "".into()
             };
 
+            let value_format_decimals = decimals(&value_format);
+
+            let missing_ranges = (0..readstat_variable_get_missing_ranges_count(variable))
+                .map(|i| MissingRange {
+                    lo: AnyValue::from(readstat_variable_get_missing_range_lo(variable, i)),
+                    hi: AnyValue::from(readstat_variable_get_missing_range_hi(variable, i)),
+                })
+                .collect();
+
 
             Variable {
                 index: index as i32,
@@ -49,6 +101,12 @@ impl Variable {
                 type_: VariableType::from(type_),
                 value_format: value_format,
                 value_labels: value_labels,
+                missing_ranges: missing_ranges,
+                measure: Measure::from(readstat_variable_get_measure(variable)),
+                alignment: Alignment::from(readstat_variable_get_alignment(variable)),
+                display_width: readstat_variable_get_display_width(variable) as i32,
+                storage_width: readstat_variable_get_storage_width(variable) as i32,
+                decimals: value_format_decimals,
             }
         }
     }
@@ -63,6 +121,67 @@ impl<'a> From<&'a str> for Variable {
             type_: VariableType::Text,
             value_format: String::new(),
             value_labels: String::new(),
+            missing_ranges: vec![],
+            measure: Measure::Unknown,
+            alignment: Alignment::Unknown,
+            display_width: 0,
+            storage_width: 0,
+            decimals: None,
+        }
+    }
+}
+
+/// Number of decimal places in a SPSS (F8.2), Stata (%9.2f) or SAS (8.2)
+/// display format, `None` when the format has none
+pub fn decimals(format: &str) -> Option<i32> {
+    let digits = &format[format.rfind('.')? + 1..];
+    let digits = digits
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    digits.parse::<i32>().ok()
+}
+
+/// Level of measurement, as set in SPSS
+#[derive(Serialize, Debug, Clone, Hash, PartialEq, Eq)]
+pub enum Measure {
+    Unknown,
+    Nominal,
+    Ordinal,
+    Scale,
+}
+
+impl From<readstat_measure_t> for Measure {
+    fn from(m: readstat_measure_t) -> Self {
+        use self::readstat_measure_t::*;
+
+        match m {
+            READSTAT_MEASURE_NOMINAL => Measure::Nominal,
+            READSTAT_MEASURE_ORDINAL => Measure::Ordinal,
+            READSTAT_MEASURE_SCALE => Measure::Scale,
+            _ => Measure::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Hash, PartialEq, Eq)]
+pub enum Alignment {
+    Unknown,
+    Left,
+    Center,
+    Right,
+}
+
+impl From<readstat_alignment_t> for Alignment {
+    fn from(a: readstat_alignment_t) -> Self {
+        use self::readstat_alignment_t::*;
+
+        match a {
+            READSTAT_ALIGNMENT_LEFT => Alignment::Left,
+            READSTAT_ALIGNMENT_CENTER => Alignment::Center,
+            READSTAT_ALIGNMENT_RIGHT => Alignment::Right,
+            _ => Alignment::Unknown,
         }
     }
 }
@@ -94,3 +213,36 @@ impl From<readstat_type_t> for VariableType {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimals() {
+        assert_eq!(decimals("F8.2"), Some(2));
+        assert_eq!(decimals("%9.3f"), Some(3));
+        assert_eq!(decimals("%10.0g"), Some(0));
+        assert_eq!(decimals("COMMA12.2"), Some(2));
+        assert_eq!(decimals("A20"), None);
+        assert_eq!(decimals("%td"), None);
+        assert_eq!(decimals(""), None);
+    }
+
+    #[test]
+    fn test_variable_identity() {
+        use std::collections::HashMap;
+
+        let variable = Variable::from("age");
+        let mut table = HashMap::new();
+        table.insert(variable.clone(), 1);
+
+        let mut changed = variable.clone();
+        changed.measure = Measure::Scale;
+        changed.label = "Age in years".to_string();
+        assert_eq!(table.get(&changed), Some(&1));
+
+        changed.index = 1;
+        assert_eq!(table.get(&changed), None);
+    }
+}
@@ -3,15 +3,22 @@ use model::value::Value;
 use model::variable::Variable;
 use report::Report;
 
-use check::Check;
+use check::{glob_match, Check};
 
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Debug;
 use std::io;
+use std::mem;
 
 use pbr::ProgressBar;
 
+/// Maps each distinct value of a variable to the rows it occurs in
+pub type Occurrences = HashMap<Value, Vec<i32>>;
+
+/// Maps each distinct combination of values to the rows it occurs in
+pub type TupleOccurrences = HashMap<Vec<Value>, Vec<i32>>;
+
 pub struct Context {
     pub config: Config,
     pub report: Report,
@@ -19,7 +26,99 @@ pub struct Context {
     pub pb: Option<ProgressBar<io::Stdout>>,
     pub variables: Vec<Variable>, // used for post-processing and iter'ing unordered hashmap
     pub value_labels: HashMap<String, HashMap<String, String>>, // used for getting value labels
-    pub frequency_table: HashMap<Variable, HashMap<Value, i32>>,
+    pub frequency_table: HashMap<Variable, Occurrences>,
+    pub row: Vec<Value>, // values of the row currently being read
+    pub tuples: HashMap<Vec<String>, TupleOccurrences>, // keyed by variable names
+    pub records: HashMap<Vec<String>, Vec<i32>>, // complete rows, only when checking duplicate records
+    pub record_variables: Option<Vec<bool>>, // by variable index, whether it is compared between records
+}
+
+impl Context {
+    pub fn new(config: &Config) -> Context {
+        let tuples = config
+            .tracked_variable_sets()
+            .into_iter()
+            .map(|names| (names, HashMap::new()))
+            .collect();
+
+        Context {
+            config: (*config).clone(),
+            report: Report::new(),
+            checks: Check::new(),
+            pb: None,
+            frequency_table: HashMap::new(),
+            value_labels: HashMap::new(),
+            variables: vec![],
+            row: vec![],
+            tuples: tuples,
+            records: HashMap::new(),
+            record_variables: None,
+        }
+    }
+
+    /// Record the row a value occurs in within the frequency table
+    pub fn add_occurrence(&mut self, value: &Value) {
+        let occurrences = self
+            .frequency_table
+            .entry(value.variable.clone())
+            .or_insert_with(HashMap::new);
+
+        occurrences
+            .entry(value.clone())
+            .or_insert_with(Vec::new)
+            .push(value.row);
+    }
+
+    /// Called once every value of the current row has been collected.
+    /// Runs the row checks and records the row against each tracked
+    /// combination of variables.
+    pub fn end_row(&mut self) {
+        let row = mem::replace(&mut self.row, vec![]);
+
+        let row_index = match row.first() {
+            Some(value) => value.row,
+            None => return,
+        };
+
+        for check in self.checks.row.iter() {
+            check(&row, &self.config, &mut self.report);
+        }
+
+        for (names, occurrences) in self.tuples.iter_mut() {
+            let key: Option<Vec<Value>> = names
+                .iter()
+                .map(|name| row.iter().find(|v| &v.variable.name == name).cloned())
+                .collect();
+
+            // skip combinations with variables not present in the file
+            if let Some(key) = key {
+                occurrences.entry(key).or_insert_with(Vec::new).push(row_index);
+            }
+        }
+
+        if let Some(ref setting) = self.config.data_integrity.duplicate_records {
+            // resolved on the first row, once every variable is known.
+            // Excluded variables, such as IDs, don't make records distinct
+            let variables = &self.variables;
+            let compared = self.record_variables.get_or_insert_with(|| {
+                variables
+                    .iter()
+                    .map(|v| !setting.setting.iter().any(|pattern| glob_match(pattern, &v.name)))
+                    .collect()
+            });
+
+            let key = row
+                .iter()
+                .filter(|value| compared.get(value.variable.index as usize).cloned().unwrap_or(true))
+                .map(|value| format!("{}", value.value))
+                .collect::<Vec<String>>();
+
+            self.records
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push(row_index);
+        }
+    }
 }
 
 impl Debug for Context {
@@ -31,7 +130,73 @@ impl Debug for Context {
             .field("variables", &self.variables)
             .field("value_labels", &self.value_labels)
             .field("frequency_table", &self.frequency_table)
+            .field("tuples", &self.tuples)
+            .field("records", &self.records)
             .finish()
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use config::Setting;
+
+    fn count_rows(row: &[Value], _config: &Config, report: &mut Report) {
+        report.metadata.raw_case_count += 1;
+        report.metadata.variable_count = row.len() as i32;
+    }
+
+    fn value(name: &str, value: &str, row: i32) -> Value {
+        let mut value = Value::from(value);
+        value.variable = Variable::from(name);
+        value.variable.index = if name == "a" { 0 } else { 1 };
+        value.row = row;
+        value
+    }
+
+    #[test]
+    fn test_end_row() {
+        let mut config = Config::default();
+        config.data_integrity.duplicate_compound_keys = Some(Setting {
+            setting: vec![vec!["b".to_string(), "a".to_string()]],
+            desc: String::new(),
+        });
+        config.data_integrity.duplicate_records = Some(Setting {
+            setting: vec!["a".to_string()],
+            desc: String::new(),
+        });
+
+        let mut context = Context::new(&config);
+        context.checks.row = vec![count_rows];
+        context.variables = vec![value("a", "", 0).variable, value("b", "", 0).variable];
+
+        for row in 0..3 {
+            context.row.push(value("a", &row.to_string(), row));
+            context.row.push(value("b", if row == 1 { "y" } else { "x" }, row));
+            context.end_row();
+        }
+
+        // an empty row is ignored
+        context.end_row();
+
+        assert!(context.row.is_empty());
+        assert_eq!(context.report.metadata.raw_case_count, 3);
+        assert_eq!(context.report.metadata.variable_count, 2);
+
+        let occurrences = &context.tuples[&vec!["b".to_string(), "a".to_string()]];
+        assert_eq!(
+            occurrences.get(&vec![Value::from("x"), Value::from("2")]),
+            Some(&vec![2])
+        );
+        assert_eq!(
+            occurrences.get(&vec![Value::from("y"), Value::from("1")]),
+            Some(&vec![1])
+        );
+
+        // the excluded variable a doesn't make rows 0 and 2 distinct
+        assert_eq!(context.record_variables, Some(vec![false, true]));
+        assert_eq!(context.records.get(&vec!["x".to_string()]), Some(&vec![0, 2]));
+        assert_eq!(context.records.get(&vec!["y".to_string()]), Some(&vec![1]));
+    }
+}
@@ -0,0 +1,184 @@
+use csv_crate::{Reader, ReaderBuilder, Trim};
+
+use config::CsvDialect;
+
+/// Delimiters tried when sniffing, in order of preference
+const DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+/// Number of records read when sniffing the delimiter
+const SNIFF_RECORDS: usize = 20;
+
+/// Build a reader over the contents for the dialect, skipping its leading
+/// rows and sniffing the delimiter when it is not set
+pub fn reader<'a>(contents: &'a str, dialect: &CsvDialect) -> Reader<&'a [u8]> {
+    let contents = skip_lines(contents, dialect.skip_rows());
+    let quote = dialect.quote.map_or(b'"', |c| c as u8);
+    let escape = dialect.escape.map(|c| c as u8);
+    let comment = dialect.comment.map(|c| c as u8);
+    let delimiter = match dialect.delimiter {
+        Some(delimiter) => delimiter as u8,
+        None => sniff_delimiter(contents, quote, escape, comment),
+    };
+
+    let mut builder = ReaderBuilder::new();
+    builder
+        .delimiter(delimiter)
+        .quote(quote)
+        .comment(comment)
+        .has_headers(dialect.has_headers());
+
+    if escape.is_some() {
+        builder.escape(escape).double_quote(false);
+    }
+
+    if dialect.trim.unwrap_or(false) {
+        builder.trim(Trim::All);
+    }
+
+    builder.from_reader(contents.as_bytes())
+}
+
+/// Pick the delimiter found the same number of times, outside of quotes, in
+/// each of the first records, preferring the most frequent. Defaults to comma
+pub fn sniff_delimiter(contents: &str, quote: u8, escape: Option<u8>, comment: Option<u8>) -> u8 {
+    let records = sniff_records(contents, quote, escape, comment);
+
+    let mut best = (b',', 0);
+
+    for delimiter in DELIMITERS.iter() {
+        let counts = records
+            .iter()
+            .map(|record| count_unquoted(record, *delimiter, quote, escape))
+            .collect::<Vec<usize>>();
+
+        if let Some(first) = counts.first() {
+            if *first > best.1 && counts.iter().all(|count| count == first) {
+                best = (*delimiter, *first);
+            }
+        }
+    }
+
+    best.0
+}
+
+/// Split the first records off the contents. Line breaks within quotes
+/// don't end a record, blank and comment lines are left out
+fn sniff_records(contents: &str, quote: u8, escape: Option<u8>, comment: Option<u8>) -> Vec<&str> {
+    let mut records = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, byte) in contents.bytes().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if quoted && Some(byte) == escape {
+            escaped = true;
+        } else if byte == quote {
+            quoted = !quoted;
+        } else if byte == b'\n' && !quoted {
+            records.push(&contents[start..i]);
+            start = i + 1;
+        }
+
+        if records.len() > SNIFF_RECORDS {
+            break;
+        }
+    }
+
+    if start < contents.len() && records.len() <= SNIFF_RECORDS {
+        records.push(&contents[start..]);
+    }
+
+    records
+        .into_iter()
+        .filter(|record| !record.trim().is_empty())
+        .filter(|record| comment.map_or(true, |c| record.as_bytes().first() != Some(&c)))
+        .take(SNIFF_RECORDS)
+        .collect()
+}
+
+fn count_unquoted(record: &str, delimiter: u8, quote: u8, escape: Option<u8>) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut count = 0;
+
+    for byte in record.bytes() {
+        if escaped {
+            escaped = false;
+        } else if quoted && Some(byte) == escape {
+            escaped = true;
+        } else if byte == quote {
+            quoted = !quoted;
+        } else if byte == delimiter && !quoted {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn skip_lines(contents: &str, rows: usize) -> &str {
+    let mut rest = contents;
+
+    for _ in 0..rows {
+        match rest.find('\n') {
+            Some(end) => rest = &rest[end + 1..],
+            None => return "",
+        }
+    }
+
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_delimiter() {
+        assert_eq!(sniff_delimiter("a,b,c\n1,2,3\n", b'"', None, None), b',');
+        assert_eq!(sniff_delimiter("a\tb\tc\n1\t2,5\t3\n", b'"', None, None), b'\t');
+        assert_eq!(sniff_delimiter("a;b\n\"1;2\";3\n", b'"', None, None), b';');
+        assert_eq!(sniff_delimiter("# a,b,c\na|b\n1|2\n", b'"', None, Some(b'#')), b'|');
+        assert_eq!(sniff_delimiter("a\n1\n", b'"', None, None), b',');
+
+        // line breaks within quotes don't end a record
+        assert_eq!(sniff_delimiter("a;b\n\"1,\n2,3\";4\n", b'"', None, None), b';');
+
+        // escaped quotes don't end a quoted field
+        assert_eq!(
+            sniff_delimiter("a;b\n\"1\\\";2,3\";4\n", b'"', Some(b'\\'), None),
+            b';'
+        );
+    }
+
+    #[test]
+    fn test_reader() {
+        let contents = "exported 2019-01-01\n# a comment\n 1 ;'x;y'\n2; z \n";
+        let dialect = CsvDialect {
+            delimiter: None,
+            quote: Some('\''),
+            escape: None,
+            has_headers: Some(false),
+            skip_rows: Some(1),
+            comment: Some('#'),
+            trim: Some(true),
+        };
+
+        let mut rdr = reader(contents, &dialect);
+        assert_eq!(rdr.headers().unwrap().len(), 2);
+
+        let records = rdr
+            .records()
+            .map(|record| record.unwrap().iter().map(|f| f.to_string()).collect())
+            .collect::<Vec<Vec<String>>>();
+        assert_eq!(
+            records,
+            vec![
+                vec!["1".to_string(), "x;y".to_string()],
+                vec!["2".to_string(), "z".to_string()],
+            ]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::io;
+
+/// Characters Windows-1252 maps the bytes 0x80 to 0x9F to, bytes it leaves
+/// undefined map to the control character of the same value
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Character encodings the CSV reader can decode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Latin1,
+    Windows1252,
+}
+
+impl Encoding {
+    /// Look up an encoding by name, ignoring case, hyphens and underscores
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        let normalised = name
+            .to_lowercase()
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>();
+
+        match normalised.as_str() {
+            "utf8" => Some(Encoding::Utf8),
+            "latin1" | "iso88591" | "l1" => Some(Encoding::Latin1),
+            "windows1252" | "cp1252" => Some(Encoding::Windows1252),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Windows1252 => "WINDOWS-1252",
+        }
+    }
+
+    /// Decode the bytes into a string, dropping any UTF-8 byte order mark
+    pub fn decode(&self, bytes: &[u8]) -> io::Result<String> {
+        match self {
+            Encoding::Utf8 => {
+                let bytes = if bytes.starts_with(b"\xEF\xBB\xBF") {
+                    &bytes[3..]
+                } else {
+                    bytes
+                };
+
+                String::from_utf8(bytes.to_vec()).map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Invalid UTF-8 at byte {}, set the input encoding if the file uses another encoding",
+                            err.utf8_error().valid_up_to()
+                        ),
+                    )
+                })
+            }
+            Encoding::Latin1 => Ok(bytes.iter().map(|b| *b as char).collect()),
+            Encoding::Windows1252 => Ok(bytes
+                .iter()
+                .map(|b| match b {
+                    0x80..=0x9F => WINDOWS_1252[(b - 0x80) as usize],
+                    _ => *b as char,
+                })
+                .collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Encoding::from_name("UTF-8"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::from_name("iso-8859-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("latin1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("Windows-1252"), Some(Encoding::Windows1252));
+        assert_eq!(Encoding::from_name("CP1252"), Some(Encoding::Windows1252));
+        assert_eq!(Encoding::from_name("EBCDIC"), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        let bytes = b"caf\xE9 \x93quoted\x94";
+
+        assert!(Encoding::Utf8.decode(bytes).is_err());
+        assert_eq!(Encoding::Latin1.decode(bytes).unwrap(), "café \u{93}quoted\u{94}");
+        assert_eq!(Encoding::Windows1252.decode(bytes).unwrap(), "café “quoted”");
+        assert_eq!(
+            Encoding::Utf8.decode("\u{FEFF}café".as_bytes()).unwrap(),
+            "café"
+        );
+    }
+}
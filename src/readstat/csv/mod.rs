@@ -1 +1,3 @@
+pub mod dialect;
+pub mod encoding;
 pub mod read;
@@ -1,35 +1,24 @@
-use csv_crate::Reader;
 use pbr::ProgressBar;
 use readstat::bindings::*;
 
-use std::collections::HashMap;
 use std::ffi::CStr;
-use std::fs;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use std::io::BufReader;
 use std::path::Path;
 
-use check::Check;
 use config::Config;
 use model::anyvalue::AnyValue;
 use model::missing::Missing;
 use model::value::Value;
-use model::variable::{Variable, VariableType};
+use model::variable::{Alignment, Measure, Variable, VariableType};
 use readstat::context::Context;
+use readstat::csv::dialect::reader;
+use readstat::csv::encoding::Encoding;
 use report::Report;
 
 pub unsafe fn read_csv(path: &str, config: &Config) -> Result<Report, io::Error> {
-    let context: *mut Context = Box::into_raw(Box::new(Context {
-        config: (*config).clone(),
-        report: Report::new(),
-        checks: Check::new(),
-        pb: None,
-        frequency_table: HashMap::new(),
-        value_labels: HashMap::new(),
-        variables: vec![],
-    }));
+    let context: *mut Context = Box::into_raw(Box::new(Context::new(config)));
 
     // init the progress bar here
     if let Some(include_progress) = config.progress {
@@ -47,47 +36,79 @@ pub unsafe fn read_csv(path: &str, config: &Config) -> Result<Report, io::Error>
         return Err(io::Error::new(io::ErrorKind::Other, "Unable to open file"));
     }
 
+    let encoding = match config.encoding {
+        Some(ref name) => match Encoding::from_name(name) {
+            Some(encoding) => encoding,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unsupported encoding for CSV files: {}", name),
+                ))
+            }
+        },
+        None => Encoding::Utf8,
+    };
+
+    // csv declares no file encoding, the values are read with this one
+    (*context).report.metadata.input_encoding = Some(encoding.name().to_string());
+
     // parse, loop & checks, build context
-    let error: readstat_error_t = match get_file_contents(path) {
-        Ok(contents) => {
-            set_metadata(path, context);
-            parse_csv(contents, context)
-        }
-        Err(_err) => readstat_error_t::READSTAT_ERROR_OPEN,
+    let result = match get_file_contents(path, encoding) {
+        Ok(contents) => parse_csv(contents, context),
+        // a decoding error says more than readstat's open error
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => Err(err),
+        Err(_err) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            ptr_to_str!(readstat_error_message(readstat_error_t::READSTAT_ERROR_OPEN)),
+        )),
     };
 
     if let Some(ref mut pb) = (*context).pb {
         pb.finish_print("");
     }
 
-    if error != readstat_error_t::READSTAT_OK {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            ptr_to_str!(readstat_error_message(error)),
-        ))
-    } else {
-        // post checks
-        for check in &(*context).checks.post {
-            check(&mut (*context));
-        }
+    result?;
 
-        Ok((*context).report.clone())
+    // post checks
+    for check in &(*context).checks.post {
+        check(&mut (*context));
     }
+
+    (*context).report.variables = (*context).variables.clone();
+
+    Ok((*context).report.clone())
 }
 
-unsafe fn parse_csv(contents: String, context: *mut Context) -> readstat_error_t {
-    let mut rdr = Reader::from_reader(contents.as_bytes());
+// Errors on malformed records, such as one with more fields than the header,
+// say which record and line they are on
+unsafe fn parse_csv(contents: String, context: *mut Context) -> Result<(), io::Error> {
+    let dialect = (*context).config.csv.clone().unwrap_or_default();
+    let mut rdr = reader(&contents, &dialect);
 
     match rdr.headers() {
         Ok(headers) => {
-            for (column_index, variable) in headers.iter().enumerate() {
+            (*context).report.metadata.variable_count = headers.len() as i32;
+
+            for (column_index, header) in headers.iter().enumerate() {
+                let name = if dialect.has_headers() {
+                    header.to_string()
+                } else {
+                    format!("V{}", column_index + 1)
+                };
+
                 let var = Variable {
                     index: column_index as i32,
-                    name: variable.to_string(),
+                    name: name,
                     label: String::new(),
                     type_: VariableType::Text,
                     value_format: String::new(),
                     value_labels: String::new(),
+                    missing_ranges: vec![],
+                    measure: Measure::Unknown,
+                    alignment: Alignment::Unknown,
+                    display_width: 0,
+                    storage_width: 0,
+                    decimals: None,
                 };
 
                 for check in &(*context).checks.variable {
@@ -96,11 +117,14 @@ unsafe fn parse_csv(contents: String, context: *mut Context) -> readstat_error_t
                 (*context).variables.push(var);
             }
         }
-        Err(_) => return readstat_error_t::READSTAT_ERROR_PARSE,
+        Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
     }
 
     for (row_index, result) in rdr.records().enumerate() {
-        let record = result.unwrap();
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
 
         for (column_index, field) in record.iter().enumerate() {
             let var = (*context)
@@ -123,67 +147,29 @@ unsafe fn parse_csv(contents: String, context: *mut Context) -> readstat_error_t
             };
 
             // build the frequency table as we collect the values
-            if let Some(ref mut value_occurence_map) = (*context).frequency_table.get_mut(&var) {
-                if let Some(occurrence) = value_occurence_map.get_mut(&value) {
-                    (*occurrence) += 1; // already exists
-                } else {
-                    // variable exists, first encounter with this value
-                    match (*context).frequency_table.get_mut(&var) {
-                        Some(val_occ_map) => val_occ_map.insert(value.clone(), 1),
-                        None => None,
-                    };
-                }
-            } else {
-                // variable not found
-                // first encounter with this variable and value
-                let mut map: HashMap<Value, i32> = HashMap::new();
-
-                map.insert(value.clone(), 1);
-                (*context).frequency_table.insert(var.clone(), map);
-            }
+            (*context).add_occurrence(&value);
 
             for check in (*context).checks.value.iter() {
                 check(&value, &(*context).config, &mut (*context).report);
             }
-        }
-    }
-
-    readstat_error_t::READSTAT_OK
-}
-
-unsafe fn set_metadata(path: &str, context: *mut Context) {
-    let _metadata = fs::metadata(path).unwrap();
-    let contents = get_file_contents(path).unwrap();
-    let mut rdr = Reader::from_reader(contents.as_bytes());
 
-    (*context).report.metadata.raw_case_count = rdr.records().count() as i32;
-
-    match rdr.headers() {
-        Ok(headers) => {
-            (*context).report.metadata.variable_count = headers.iter().count() as i32;
+            (*context).row.push(value);
         }
-        Err(_) => (),
-    }
 
-    // (*context).report.metadata.creation_time = {
-    //     let sys_time = metadata.created().unwrap_or(time::UNIX_EPOCH);
-    //     sys_time.duration_since(time::UNIX_EPOCH).unwrap().as_secs() as i64
-    // };
+        (*context).end_row();
+        (*context).report.metadata.raw_case_count = row_index as i32 + 1;
+    }
 
-    // (*context).report.metadata.modified_time = {
-    //     let sys_time = metadata.modified().unwrap();
-    //     sys_time.duration_since(time::UNIX_EPOCH).unwrap().as_secs() as i64
-    // };
+    Ok(())
 }
 
-fn get_file_contents(path: &str) -> io::Result<String> {
-    let file = File::open(path)?;
-    let mut buf_reader = BufReader::new(file);
-    let mut contents = String::new();
+fn get_file_contents(path: &str, encoding: Encoding) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut bytes = vec![];
 
-    buf_reader.read_to_string(&mut contents)?;
+    file.read_to_end(&mut bytes)?;
 
-    Ok(contents)
+    encoding.decode(&bytes)
 }
 
 // fn as_fixed_size(a: &mut [i8], s: &mut String) {
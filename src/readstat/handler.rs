@@ -93,11 +93,10 @@ pub unsafe extern "C" fn value_handler(
 ) -> c_int {
     let context = ctx as *mut Context;
 
-    let var = (*context)
-        .variables
-        .iter()
-        .nth(readstat_variable_get_index(variable) as usize)
-        .unwrap();
+    let variable_index = readstat_variable_get_index(variable) as usize;
+    let end_of_row = variable_index + 1 == (*context).variables.len();
+
+    let var = (*context).variables.iter().nth(variable_index).unwrap();
     let anyvalue = AnyValue::from(value);
 
     // determine the MISSINGESS
@@ -130,30 +129,17 @@ pub unsafe extern "C" fn value_handler(
     };
 
     // build the frequency table as we collect the values
-    if let Some(ref mut value_occurence_map) = (*context).frequency_table.get_mut(&var) {
-        // check it has been pushed
-        if let Some(occurrence) = value_occurence_map.get_mut(&value) {
-            (*occurrence) += 1; // already exists
-        } else {
-            // variable exists, first encounter with this value
-            match (*context).frequency_table.get_mut(&var) {
-                Some(val_occ_map) => val_occ_map.insert(value.clone(), 1),
-                None => None,
-            };
-        }
-    } else {
-        // variable not found
-        // first encounter with this variable and value
-        let mut map: HashMap<Value, i32> = HashMap::new();
-
-        map.insert(value.clone(), 1);
-        (*context).frequency_table.insert(var.clone(), map);
-    }
+    (*context).add_occurrence(&value);
 
     for check in (*context).checks.value.iter() {
         check(&value, &(*context).config, &mut (*context).report);
     }
 
+    (*context).row.push(value);
+    if end_of_row {
+        (*context).end_row();
+    }
+
     return READSTAT_HANDLER_OK as c_int;
 }
 
@@ -1,4 +1,3 @@
-use check::Check;
 use config::Config;
 use report::Report;
 
@@ -8,8 +7,6 @@ use readstat::handler::*;
 
 use readstat::csv::read;
 
-use std::collections::HashMap;
-
 use std::ffi::{CStr, CString};
 use std::io;
 use std::os::raw::{c_char, c_void};
@@ -71,15 +68,7 @@ type ParseFn = unsafe extern "C" fn(
 
 /// Read the file using a given ParseFn
 unsafe fn _read(path: &str, config: &Config, file_parser: ParseFn) -> Result<Report, io::Error> {
-    let context: *mut Context = Box::into_raw(Box::new(Context {
-        config: (*config).clone(),
-        report: Report::new(),
-        checks: Check::new(),
-        pb: None,
-        frequency_table: HashMap::new(),
-        value_labels: HashMap::new(),
-        variables: vec![],
-    }));
+    let context: *mut Context = Box::into_raw(Box::new(Context::new(config)));
 
     // init the progress bar here
     if let Some(include_progress) = config.progress {
@@ -106,6 +95,18 @@ unsafe fn _read(path: &str, config: &Config, file_parser: ParseFn) -> Result<Rep
     readstat_set_value_label_handler(parser, Some(value_label_handler));
     readstat_set_progress_handler(parser, Some(progress_handler));
 
+    // overrides the encoding declared by the file
+    if let Some(ref encoding) = config.encoding {
+        let error = readstat_set_file_character_encoding(parser, str_to_ptr!(encoding.as_str()));
+
+        if error != readstat_error_t::READSTAT_OK {
+            readstat_parser_free(parser);
+            return Err(handle_error(error));
+        }
+
+        (*context).report.metadata.input_encoding = Some(encoding.clone());
+    }
+
     let path_to_file = str_to_ptr!(path);
     let error = file_parser(parser, path_to_file, context as *mut c_void);
 
@@ -124,6 +125,8 @@ unsafe fn _read(path: &str, config: &Config, file_parser: ParseFn) -> Result<Rep
             check(&mut (*context));
         }
 
+        (*context).report.variables = (*context).variables.clone();
+
         Ok((*context).report.clone())
     }
 }
@@ -178,6 +181,45 @@ mod tests {
         let report = ok!(read_csv("test/mtcars.csv", &config));
         assert_eq!(report.metadata.variable_count, 12);
         assert_eq!(report.metadata.raw_case_count, 32);
+        assert_eq!(report.metadata.file_encoding, None);
+        assert_eq!(report.metadata.input_encoding, Some("UTF-8".to_string()));
+    }
+
+    #[test]
+    fn test_read_csv_ragged() {
+        let config = Config::default();
+
+        let err = read_csv("test/ragged.csv", &config).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line: 3"));
+    }
+
+    #[test]
+    fn test_read_csv_outliers() {
+        use check::CheckName::NumericOutliers;
+        use config::{OutlierMethod, Outliers, Setting};
+
+        let mut config = Config::default();
+        config.metadata_only = Some(false);
+        config.data_integrity.outliers = Some(Setting {
+            setting: Outliers {
+                method: OutlierMethod::Iqr,
+                threshold: 1.5,
+                variables: Some(vec!["hp".to_string()]),
+            },
+            desc: String::new(),
+        });
+
+        // CSV columns are read as text, their numeric values are checked
+        let report = ok!(read_csv("test/mtcars.csv", &config));
+        let status = ok!(report.summary.get(&NumericOutliers));
+        assert_eq!((status.pass, status.fail), (0, 1));
+
+        let values = status
+            .into_iter()
+            .map(|l| l.value.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![Some("335.00".to_string())]);
     }
 
     #[test]
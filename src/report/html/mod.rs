@@ -107,17 +107,22 @@ fn locators_table<'a>(name: String, status: Status) -> Box<RenderBox> {
             table(id=name.to_lowercase().replace(" ", "_"),
                   class="table table-striped table-bordered d-none") {
                 tr {
-                    th(scope="col") : "# (limited to 1000)";
+                    th(scope="col") : "#";
                     th(scope="col") : "Variable";
                     th(scope="col") : "Row number";
+                    th(scope="col") : "Value";
+                    th(scope="col") : "Note";
                 }
 
-                @ for (i, pair) in status.into_iter().take(1000).enumerate() {
+                @ for (i, locator) in status.into_iter().enumerate() {
                     tr(class="locator") {
                         td(scope="row") : i + 1;
-                        td : format!("{}", pair.variable_name);
+                        td : format!("{}", locator.variable_name);
 
-                        : value_if_positive(pair.value_index, "-");
+                        : value_if_positive(locator.value_index, "-");
+
+                        td : locator.value.clone().unwrap_or("-".to_string());
+                        td : locator.note.clone().unwrap_or("-".to_string());
                     }
                 }
             }
@@ -127,7 +132,7 @@ fn locators_table<'a>(name: String, status: Status) -> Box<RenderBox> {
 
 fn value_if_positive(value: i32, default: &'static str) -> Box<Render> {
     box_html! {
-        @ if value <= 0 {
+        @ if value < 0 {
             td : default;
         } else {
             td : value;
@@ -259,6 +264,12 @@ fn metadata<'a>(metadata: &'a Metadata) -> Box<RenderBox + 'a> {
             }
         }
 
+        div(class="row metadata") {
+            @ if let Some(ref input_encoding) = &metadata.input_encoding {
+                strong : format!("Input Encoding: {}", input_encoding);
+            }
+        }
+
         div(class="row metadata") {
             strong : format!("Compression type: {}",
                       &metadata.compression);
@@ -1,7 +1,8 @@
 
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::collections::HashMap;
-use std::collections::HashSet;
+use std::fmt::Display;
 use std::iter::IntoIterator;
 use std::slice::Iter;
 
@@ -16,6 +17,9 @@ pub mod html;
 pub struct Report {
     pub metadata: Metadata,
     pub summary: HashMap<CheckName, Status>,
+    pub missing: BTreeMap<String, MissingPercentages>, // keyed by variable name
+    pub risk: DisclosureRisk,
+    pub variables: Vec<Variable>,
 }
 
 impl Report {
@@ -23,6 +27,9 @@ impl Report {
         Report {
             metadata: Metadata::new(),
             summary: HashMap::new(),
+            missing: BTreeMap::new(),
+            risk: DisclosureRisk::default(),
+            variables: vec![],
         }
     }
 }
@@ -56,6 +63,7 @@ pub struct Metadata {
     pub file_label: String,
     pub file_format_version: i64,
     pub file_encoding: Option<String>,
+    pub input_encoding: Option<String>,
 
     pub compression: String,
 }
@@ -76,6 +84,7 @@ impl Metadata {
             file_label: "".into(),
             file_format_version: 0,
             file_encoding: None,
+            input_encoding: None,
 
             compression: "".into(),
         }
@@ -92,6 +101,30 @@ impl Metadata {
     }
 }
 
+/// Percentage of the values of a variable that are missing, by type of missing
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct MissingPercentages {
+    pub system: f32,
+    pub defined: f32,
+    pub tagged: f32,
+}
+
+/// Disclosure risk of the records which are special uniques on the key
+/// variables, see `check::suda`
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct DisclosureRisk {
+    pub records: Vec<RecordRisk>, // riskiest records first
+    pub contributions: BTreeMap<String, f64>, // score contributed, keyed by key variable name
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RecordRisk {
+    pub row: i32,
+    pub score: f64,
+    pub values: Vec<String>,
+    pub msus: Vec<Vec<String>>, // minimal sample uniques, as key variable names
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq)]
 pub enum Category {
     BasicFile,
@@ -127,7 +160,7 @@ pub struct Status {
     pub pass: i32,
     pub fail: i32,
     pub desc: String,
-    pub locators: Option<HashSet<Locator>>,
+    pub locators: Option<BTreeSet<Locator>>,
     pub category: Category,
 }
 
@@ -136,13 +169,11 @@ impl<'a> IntoIterator for &'a Status {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let mut lst = vec![];
+        // locators are kept ordered by the set
         if let Some(locators) = &self.locators {
-            lst = locators.iter().collect::<Vec<Self::Item>>();
-            lst.sort_by(|a, b| a.partial_cmp(b).unwrap());
-            lst.into_iter()
+            locators.iter().collect::<Vec<Self::Item>>().into_iter()
         } else {
-            lst.into_iter()
+            vec![].into_iter()
         }
     }
 }
@@ -157,18 +188,51 @@ impl Status {
             category,
         }
     }
+
+    /// Add a locator to the status. At most `limit` locators are kept, when
+    /// the limit is exceeded the last locator in order is dropped.
+    pub fn add_locator(&mut self, locator: Locator, limit: usize) {
+        let locators = self.locators.get_or_insert_with(BTreeSet::new);
+        locators.insert(locator);
+
+        if locators.len() > limit {
+            let last = locators.iter().next_back().cloned();
+            if let Some(ref last) = last {
+                locators.remove(last);
+            }
+        }
+    }
 }
 
+/// Locates a failure in the data file, the variable, the row
+/// (`value_index`) and the offending value. Variable level failures have a
+/// `value_index` of -1 and no value. The note carries any extra detail the
+/// check has on the failure.
 #[derive(Serialize, Debug, Clone, Hash)]
 pub struct Locator {
     pub variable_name: String,
     pub variable_index: i32,
     pub value_index: i32,
+    pub value: Option<String>,
+    pub note: Option<String>,
 }
 
 impl Ord for Locator {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.variable_index.cmp(&other.variable_index)
+        (
+            self.variable_index,
+            self.value_index,
+            &self.value,
+            &self.note,
+            &self.variable_name,
+        )
+            .cmp(&(
+                other.variable_index,
+                other.value_index,
+                &other.value,
+                &other.note,
+                &other.variable_name,
+            ))
     }
 }
 
@@ -180,7 +244,7 @@ impl PartialOrd for Locator {
 
 impl PartialEq for Locator {
     fn eq(&self, other: &Self) -> bool {
-        self.variable_index == other.variable_index
+        self.cmp(other) == Ordering::Equal
     }
 }
 impl Eq for Locator {}
@@ -191,6 +255,8 @@ impl From<&Variable> for Locator {
             variable_name: variable.name.clone(),
             variable_index: variable.index,
             value_index: -1,
+            value: None,
+            note: None,
         }
     }
 }
@@ -205,7 +271,52 @@ impl Locator {
             variable_name: variable_name,
             variable_index: variable_index,
             value_index: value_index,
+            value: None,
+            note: None,
         }
     }
+
+    /// Attach the offending value to the locator
+    pub fn with_value<T: Display>(mut self, value: T) -> Locator {
+        self.value = Some(format!("{}", value));
+        self
+    }
+
+    /// Attach a note describing the failure to the locator
+    pub fn with_note<T: Display>(mut self, note: T) -> Locator {
+        self.note = Some(format!("{}", note));
+        self
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locator_ordering() {
+        let a = Locator::new("foo".to_string(), 0, 1).with_value("x");
+        let b = Locator::new("foo".to_string(), 0, 2).with_value("x");
+        let c = Locator::new("bar".to_string(), 1, 0);
+
+        assert!(a < b);
+        assert!(b < c);
+        assert!(a != Locator::new("foo".to_string(), 0, 1));
+        assert_eq!(a, Locator::new("foo".to_string(), 0, 1).with_value("x"));
+    }
+
+    #[test]
+    fn test_add_locator_is_bounded() {
+        let mut status = Status::new("desc", Category::DataIntegrity);
+
+        for row in (0..10).rev() {
+            status.add_locator(Locator::new("foo".to_string(), 0, row), 3);
+        }
+
+        let rows = status
+            .into_iter()
+            .map(|l| l.value_index)
+            .collect::<Vec<i32>>();
+        assert_eq!(rows, vec![0, 1, 2]);
+    }
+}